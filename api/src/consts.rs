@@ -1,4 +1,44 @@
+use solana_program::pubkey;
+use steel::*;
+
 pub const FUND: &[u8] = b"fund";
 pub const POSITION: &[u8] = b"position";
 pub const PAYOUT: &[u8] = b"payout";
 pub const TOKEN_ACCOUNT: &[u8] = b"token_account";
+pub const PROTOCOL_CONFIG: &[u8] = b"protocol_config";
+pub const MANDATE: &[u8] = b"mandate";
+
+pub const ALLOWLIST: &[u8] = b"allowlist";
+
+/// Seed of the temporary account `DepositSol` and `ClaimPayoutSol` wrap lamports in.
+pub const WSOL: &[u8] = b"wsol";
+
+/// Swap program approved when the protocol is initialized.
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+/// Capacity of the swap program allowlist in the protocol config.
+pub const MAX_SWAP_PROGRAMS: usize = 8;
+
+/// Oracle prices older than this many seconds are rejected.
+pub const MAX_PRICE_AGE: i64 = 60;
+
+/// The only key allowed to initialize the protocol config.
+pub const INITIAL_PROTOCOL_ADMIN: Pubkey = pubkey!("H61JjSDPCwvAs1k2vaPAX6d917Pu4dPWykcexvXXzGph");
+
+/// Fixed point scale of net asset value per share, e.g. the high-water mark.
+pub const NAV_PER_SHARE_SCALE: u64 = 1_000_000_000;
+
+/// Denominator for fees expressed in basis points.
+pub const MAX_BPS: u64 = 10_000;
+
+/// Upper bound for the protocol fee, 10%.
+pub const MAX_PROTOCOL_FEE_BPS: u64 = 1_000;
+
+/// Upper bound for the annual management fee, 10%.
+pub const MAX_MANAGEMENT_FEE_BPS: u64 = 1_000;
+
+/// Management fees are prorated over a 365 day year.
+pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+/// Upper bound for the authority fee, so that it and the protocol fee never exceed a payout.
+pub const MAX_AUTHORITY_FEE_BPS: u64 = MAX_BPS - MAX_PROTOCOL_FEE_BPS;
@@ -11,6 +11,52 @@ pub enum YokoProgramError {
     ErrorInsertingOtherMint = 2,
     #[error("Error removing other mint")]
     ErrorRemovingOtherMint = 3,
+    #[error("Invalid oracle price")]
+    InvalidPrice = 4,
+    #[error("Stale oracle price")]
+    StalePrice = 5,
+    #[error("Protocol is paused")]
+    ProtocolPaused = 6,
+    #[error("Swap program is not allowed")]
+    SwapProgramNotAllowed = 7,
+    #[error("Swap output is below the minimum")]
+    SlippageExceeded = 8,
+    #[error("Swap price deviates too far from the oracle price")]
+    PriceDeviation = 9,
+    #[error("Price update is for another feed than the fund expects")]
+    PriceFeedMismatch = 10,
+    #[error("Mint is not in the fund mandate")]
+    MintNotInMandate = 11,
+    #[error("Swap exceeds the concentration limit")]
+    ConcentrationLimitExceeded = 12,
+    #[error("Swap exceeds the trade size limit")]
+    TradeSizeLimitExceeded = 13,
+    #[error("Swap is too soon after the previous one")]
+    SwapTooSoon = 14,
+    #[error("Deposit exceeds the fund deposit cap")]
+    DepositCapExceeded = 15,
+    #[error("Deposit is below the fund minimum")]
+    DepositTooSmall = 16,
+    #[error("Deposit exceeds the per-position limit")]
+    PositionLimitExceeded = 17,
+    #[error("Depositor is not allowed to open a position in this fund")]
+    DepositorNotAllowed = 18,
+    #[error("Position is locked after the last deposit")]
+    PositionLocked = 19,
+    #[error("Fund requires a redemption request before withdrawing")]
+    RedemptionNotRequested = 20,
+    #[error("Redemption notice period has not elapsed")]
+    NoticePeriodNotElapsed = 21,
+    #[error("Fund is paused")]
+    FundPaused = 22,
+    #[error("Fund main mint is not native SOL")]
+    NotNativeMint = 23,
+    #[error("Payout has not been claimed by every position")]
+    PayoutNotFullyClaimed = 24,
+    #[error("Payout claim deadline has not passed")]
+    PayoutNotExpired = 25,
+    #[error("Payout is too small to pay the performance fee")]
+    PayoutBelowPerformanceFee = 26,
 }
 
 error!(YokoProgramError);
@@ -0,0 +1,618 @@
+use std::str::FromStr;
+
+use steel::*;
+
+use crate::instruction::{
+    CreateFund, SetAccessPolicy, SetDepositLimits, SetFundPaused, SetRedemptionRules,
+    SetRiskLimits, SetSwapProgram,
+};
+
+/// Version of the event layouts, logged ahead of every event. Bumped whenever a layout changes so
+/// that decoders can tell old layouts apart.
+pub const EVENT_VERSION: u8 = 1;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum YokoProgramEvent {
+    FundCreated = 0,
+    PositionCreated = 1,
+    Deposited = 2,
+    Withdrawn = 3,
+    PayoutCreated = 4,
+    PayoutClaimed = 5,
+    PayoutClosed = 6,
+    Swapped = 7,
+    FundTokenAccountCreated = 8,
+    AuthorityProposed = 9,
+    AuthorityAccepted = 10,
+    ProtocolUpdated = 11,
+    ManagementFeeAccrued = 12,
+    SwapProgramSet = 13,
+    MandateCreated = 14,
+    RiskLimitsSet = 15,
+    DepositLimitsSet = 16,
+    AccessPolicySet = 17,
+    AllowlistUpdated = 18,
+    RedemptionRulesSet = 19,
+    RedemptionRequested = 20,
+    FundPausedSet = 21,
+    WithdrawnInKind = 22,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct FundCreated {
+    pub fund: Pubkey,
+    pub authority: Pubkey,
+    pub main_mint: Pubkey,
+    pub params: CreateFund,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct PositionCreated {
+    pub fund: Pubkey,
+    pub position: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Emitted by `Deposit`, `DepositSol` and `DepositInKind`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Deposited {
+    pub fund: Pubkey,
+    pub position: Pubkey,
+    pub mint: Pubkey,
+    /// Amount of `mint` the fund received, net of any transfer fee.
+    pub amount: u64,
+    /// Worth of the deposit in main mint base units, which the shares are issued for.
+    pub value: u64,
+    pub shares: u64,
+    pub total_shares: u64,
+}
+
+/// Emitted by `Withdraw` after a `WithdrawnInKind` for each of the fund's other mints.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Withdrawn {
+    pub fund: Pubkey,
+    pub position: Pubkey,
+    pub shares: u64,
+    pub total_shares: u64,
+    /// Amount of the main mint the depositor received, net of any transfer fee.
+    pub main_amount: u64,
+    /// Number of other mints withdrawn in kind, each logged as a `WithdrawnInKind`.
+    pub in_kind_mints: u64,
+}
+
+/// Emitted by `Withdraw` for each of the fund's other mints.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct WithdrawnInKind {
+    pub fund: Pubkey,
+    pub position: Pubkey,
+    pub mint: Pubkey,
+    /// Amount of `mint` the depositor received, net of any transfer fee.
+    pub amount: u64,
+}
+
+/// Emitted by `CreatePayout` and `CreatePayoutInKind`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct PayoutCreated {
+    pub fund: Pubkey,
+    pub payout: Pubkey,
+    pub mint: Pubkey,
+    pub counter: u64,
+    pub amount: u64,
+    pub protocol_fee: u64,
+    pub authority_fee: u64,
+    /// Amount the payout token account received for the depositors.
+    pub payout_amount: u64,
+    pub claim_deadline: i64,
+}
+
+/// Emitted by `ClaimPayout`, `ClaimPayouts` and `ClaimPayoutSol` for every payout claimed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct PayoutClaimed {
+    pub fund: Pubkey,
+    pub payout: Pubkey,
+    pub position: Pubkey,
+    pub counter: u64,
+    pub amount: u64,
+}
+
+/// Emitted by `ClosePayout` and `ReclaimPayout`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct PayoutClosed {
+    pub fund: Pubkey,
+    pub payout: Pubkey,
+    pub counter: u64,
+    /// Amount left in the payout token account and swept back into the fund.
+    pub reclaimed_amount: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Swapped {
+    pub fund: Pubkey,
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub in_amount: u64,
+    /// Amount the fund received, net of any transfer fee.
+    pub out_amount: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct FundTokenAccountCreated {
+    pub fund: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AuthorityProposed {
+    pub fund: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AuthorityAccepted {
+    pub fund: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Emitted by `InitializeProtocol` and `UpdateProtocol`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ProtocolUpdated {
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub protocol_fee_bps: u64,
+    pub paused: u8,
+    pub _padding: [u8; 7],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ManagementFeeAccrued {
+    pub fund: Pubkey,
+    pub fund_value: u64,
+    pub fee: u64,
+    /// Amount transferred to the fund authority, the fee capped by the main mint balance.
+    pub amount: u64,
+    /// Timestamp the fee is accrued until. Behind the current time when `amount` fell short of
+    /// `fee`.
+    pub accrued_at: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SwapProgramSet {
+    pub params: SetSwapProgram,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct MandateCreated {
+    pub fund: Pubkey,
+    pub mandate: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct RiskLimitsSet {
+    pub fund: Pubkey,
+    pub params: SetRiskLimits,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct DepositLimitsSet {
+    pub fund: Pubkey,
+    pub params: SetDepositLimits,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AccessPolicySet {
+    pub fund: Pubkey,
+    pub params: SetAccessPolicy,
+}
+
+/// Emitted by `AddToAllowlist` and `RemoveFromAllowlist`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AllowlistUpdated {
+    pub fund: Pubkey,
+    pub depositor: Pubkey,
+    /// Non-zero when the depositor was added.
+    pub allowed: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct RedemptionRulesSet {
+    pub fund: Pubkey,
+    pub params: SetRedemptionRules,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct RedemptionRequested {
+    pub fund: Pubkey,
+    pub position: Pubkey,
+    pub requested_at: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct FundPausedSet {
+    pub fund: Pubkey,
+    pub params: SetFundPaused,
+}
+
+/// Logs the event with `sol_log_data` as two fields: `[EVENT_VERSION, discriminator]` and the
+/// event bytes.
+macro_rules! impl_event {
+    ($discriminator_name:ident, $struct_name:ident) => {
+        impl Loggable for $struct_name {
+            fn log(&self) {
+                solana_program::log::sol_log_data(&[
+                    &[EVENT_VERSION, $discriminator_name::$struct_name.into()],
+                    bytemuck::bytes_of(self),
+                ]);
+            }
+
+            fn log_return(&self) {
+                solana_program::program::set_return_data(
+                    &[
+                        &[EVENT_VERSION, $discriminator_name::$struct_name.into()],
+                        bytemuck::bytes_of(self),
+                    ]
+                    .concat(),
+                );
+            }
+        }
+    };
+}
+
+impl_event!(YokoProgramEvent, FundCreated);
+impl_event!(YokoProgramEvent, PositionCreated);
+impl_event!(YokoProgramEvent, Deposited);
+impl_event!(YokoProgramEvent, Withdrawn);
+impl_event!(YokoProgramEvent, PayoutCreated);
+impl_event!(YokoProgramEvent, PayoutClaimed);
+impl_event!(YokoProgramEvent, PayoutClosed);
+impl_event!(YokoProgramEvent, Swapped);
+impl_event!(YokoProgramEvent, FundTokenAccountCreated);
+impl_event!(YokoProgramEvent, AuthorityProposed);
+impl_event!(YokoProgramEvent, AuthorityAccepted);
+impl_event!(YokoProgramEvent, ProtocolUpdated);
+impl_event!(YokoProgramEvent, ManagementFeeAccrued);
+impl_event!(YokoProgramEvent, SwapProgramSet);
+impl_event!(YokoProgramEvent, MandateCreated);
+impl_event!(YokoProgramEvent, RiskLimitsSet);
+impl_event!(YokoProgramEvent, DepositLimitsSet);
+impl_event!(YokoProgramEvent, AccessPolicySet);
+impl_event!(YokoProgramEvent, AllowlistUpdated);
+impl_event!(YokoProgramEvent, RedemptionRulesSet);
+impl_event!(YokoProgramEvent, RedemptionRequested);
+impl_event!(YokoProgramEvent, FundPausedSet);
+impl_event!(YokoProgramEvent, WithdrawnInKind);
+
+/// An event decoded from the transaction logs.
+#[derive(Clone, Copy, Debug)]
+pub enum Event {
+    FundCreated(FundCreated),
+    PositionCreated(PositionCreated),
+    Deposited(Deposited),
+    Withdrawn(Withdrawn),
+    PayoutCreated(PayoutCreated),
+    PayoutClaimed(PayoutClaimed),
+    PayoutClosed(PayoutClosed),
+    Swapped(Swapped),
+    FundTokenAccountCreated(FundTokenAccountCreated),
+    AuthorityProposed(AuthorityProposed),
+    AuthorityAccepted(AuthorityAccepted),
+    ProtocolUpdated(ProtocolUpdated),
+    ManagementFeeAccrued(ManagementFeeAccrued),
+    SwapProgramSet(SwapProgramSet),
+    MandateCreated(MandateCreated),
+    RiskLimitsSet(RiskLimitsSet),
+    DepositLimitsSet(DepositLimitsSet),
+    AccessPolicySet(AccessPolicySet),
+    AllowlistUpdated(AllowlistUpdated),
+    RedemptionRulesSet(RedemptionRulesSet),
+    RedemptionRequested(RedemptionRequested),
+    FundPausedSet(FundPausedSet),
+    WithdrawnInKind(WithdrawnInKind),
+}
+
+/// Decodes the events the program logged in a transaction's log messages, in order. Data logged
+/// by other programs, including the ones the program calls, and events of other versions are
+/// skipped. A failed invocation fails the whole transaction and rolls back its changes, so a
+/// failed transaction has no events.
+pub fn decode_events<S: AsRef<str>>(log_messages: &[S]) -> Vec<Event> {
+    // Whether each program on the invocation stack is this program.
+    let mut invocations = vec![];
+    let mut events = vec![];
+
+    for log_message in log_messages {
+        let log_message = log_message.as_ref();
+
+        if let Some(data) = log_message.strip_prefix("Program data: ") {
+            if invocations.last() == Some(&true) {
+                events.extend(decode_event(data));
+            }
+            continue;
+        }
+
+        let words = log_message.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            ["Program", program_id, "invoke", _depth] if is_program_id(program_id) => {
+                invocations.push(*program_id == crate::ID.to_string());
+            }
+            ["Program", program_id, "success"] if is_program_id(program_id) => {
+                invocations.pop();
+            }
+            ["Program", program_id, "failed:", ..] if is_program_id(program_id) => {
+                return vec![];
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Tells program ids apart from the `Program log:` and similar prefixes.
+fn is_program_id(word: &str) -> bool {
+    Pubkey::from_str(word).is_ok()
+}
+
+/// Decodes the base64 fields of one `Program data:` log message.
+fn decode_event(data: &str) -> Option<Event> {
+    let fields = data
+        .split_whitespace()
+        .map(base64::decode)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let [header, body] = fields.as_slice() else {
+        return None;
+    };
+    let [EVENT_VERSION, discriminator] = header.as_slice() else {
+        return None;
+    };
+
+    let event = match YokoProgramEvent::try_from(*discriminator).ok()? {
+        YokoProgramEvent::FundCreated => Event::FundCreated(read(body)?),
+        YokoProgramEvent::PositionCreated => Event::PositionCreated(read(body)?),
+        YokoProgramEvent::Deposited => Event::Deposited(read(body)?),
+        YokoProgramEvent::Withdrawn => Event::Withdrawn(read(body)?),
+        YokoProgramEvent::PayoutCreated => Event::PayoutCreated(read(body)?),
+        YokoProgramEvent::PayoutClaimed => Event::PayoutClaimed(read(body)?),
+        YokoProgramEvent::PayoutClosed => Event::PayoutClosed(read(body)?),
+        YokoProgramEvent::Swapped => Event::Swapped(read(body)?),
+        YokoProgramEvent::FundTokenAccountCreated => Event::FundTokenAccountCreated(read(body)?),
+        YokoProgramEvent::AuthorityProposed => Event::AuthorityProposed(read(body)?),
+        YokoProgramEvent::AuthorityAccepted => Event::AuthorityAccepted(read(body)?),
+        YokoProgramEvent::ProtocolUpdated => Event::ProtocolUpdated(read(body)?),
+        YokoProgramEvent::ManagementFeeAccrued => Event::ManagementFeeAccrued(read(body)?),
+        YokoProgramEvent::SwapProgramSet => Event::SwapProgramSet(read(body)?),
+        YokoProgramEvent::MandateCreated => Event::MandateCreated(read(body)?),
+        YokoProgramEvent::RiskLimitsSet => Event::RiskLimitsSet(read(body)?),
+        YokoProgramEvent::DepositLimitsSet => Event::DepositLimitsSet(read(body)?),
+        YokoProgramEvent::AccessPolicySet => Event::AccessPolicySet(read(body)?),
+        YokoProgramEvent::AllowlistUpdated => Event::AllowlistUpdated(read(body)?),
+        YokoProgramEvent::RedemptionRulesSet => Event::RedemptionRulesSet(read(body)?),
+        YokoProgramEvent::RedemptionRequested => Event::RedemptionRequested(read(body)?),
+        YokoProgramEvent::FundPausedSet => Event::FundPausedSet(read(body)?),
+        YokoProgramEvent::WithdrawnInKind => Event::WithdrawnInKind(read(body)?),
+    };
+
+    Some(event)
+}
+
+/// Reads an event from decoded log data, which carries no alignment guarantee.
+fn read<T: Pod>(body: &[u8]) -> Option<T> {
+    bytemuck::try_pod_read_unaligned(body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_log<T: Pod>(version: u8, event: YokoProgramEvent, body: &T) -> String {
+        format!(
+            "Program data: {} {}",
+            base64::encode([version, event.into()]),
+            base64::encode(bytemuck::bytes_of(body))
+        )
+    }
+
+    fn withdrawn(shares: u64) -> Withdrawn {
+        Withdrawn {
+            shares,
+            ..Withdrawn::zeroed()
+        }
+    }
+
+    fn withdrawn_log(shares: u64) -> String {
+        data_log(
+            EVENT_VERSION,
+            YokoProgramEvent::Withdrawn,
+            &withdrawn(shares),
+        )
+    }
+
+    fn withdrawn_shares(events: &[Event]) -> Vec<u64> {
+        events
+            .iter()
+            .map(|event| match event {
+                Event::Withdrawn(withdrawn) => withdrawn.shares,
+                event => panic!("unexpected event {:?}", event),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decodes_events_of_the_program() {
+        let id = crate::ID.to_string();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            "Program log: Instruction: Withdraw".to_string(),
+            withdrawn_log(5),
+            format!("Program {} consumed 12345 of 200000 compute units", id),
+            format!("Program {} success", id),
+        ];
+
+        assert_eq!(withdrawn_shares(&decode_events(&logs)), [5]);
+    }
+
+    #[test]
+    fn skips_data_of_nested_invokes_of_other_programs() {
+        let id = crate::ID.to_string();
+        let other = Pubkey::new_unique().to_string();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            withdrawn_log(1),
+            format!("Program {} invoke [2]", other),
+            // Data of another program that happens to look like an event.
+            withdrawn_log(2),
+            format!("Program {} success", other),
+            withdrawn_log(3),
+            format!("Program {} success", id),
+            format!("Program {} invoke [1]", other),
+            withdrawn_log(4),
+            format!("Program {} invoke [2]", id),
+            withdrawn_log(5),
+            format!("Program {} success", id),
+            format!("Program {} success", other),
+        ];
+
+        assert_eq!(withdrawn_shares(&decode_events(&logs)), [1, 3, 5]);
+    }
+
+    #[test]
+    fn ignores_log_messages_that_look_like_results() {
+        let id = crate::ID.to_string();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            "Program log: success".to_string(),
+            "Program log: failed: not really".to_string(),
+            withdrawn_log(5),
+            format!("Program {} success", id),
+        ];
+
+        assert_eq!(withdrawn_shares(&decode_events(&logs)), [5]);
+    }
+
+    #[test]
+    fn failed_invokes_have_no_events() {
+        let id = crate::ID.to_string();
+        let other = Pubkey::new_unique().to_string();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            withdrawn_log(1),
+            format!("Program {} success", id),
+            format!("Program {} invoke [1]", id),
+            withdrawn_log(2),
+            format!("Program {} invoke [2]", other),
+            format!("Program {} failed: custom program error: 0x1", other),
+            format!("Program {} failed: custom program error: 0x1", id),
+        ];
+
+        assert!(decode_events(&logs).is_empty());
+    }
+
+    #[test]
+    fn skips_events_of_other_versions() {
+        let id = crate::ID.to_string();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            data_log(
+                EVENT_VERSION + 1,
+                YokoProgramEvent::Withdrawn,
+                &withdrawn(1),
+            ),
+            withdrawn_log(2),
+            format!("Program {} success", id),
+        ];
+
+        assert_eq!(withdrawn_shares(&decode_events(&logs)), [2]);
+    }
+
+    #[test]
+    fn skips_malformed_data() {
+        let id = crate::ID.to_string();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            "Program data: not-base64".to_string(),
+            format!(
+                "Program data: {}",
+                base64::encode([EVENT_VERSION, YokoProgramEvent::Withdrawn.into()])
+            ),
+            format!(
+                "Program data: {} {}",
+                base64::encode([EVENT_VERSION, 255]),
+                base64::encode(bytemuck::bytes_of(&withdrawn(1)))
+            ),
+            format!(
+                "Program data: {} {}",
+                base64::encode([EVENT_VERSION, YokoProgramEvent::Withdrawn.into()]),
+                base64::encode([0; 8])
+            ),
+            withdrawn_log(2),
+            format!("Program {} success", id),
+        ];
+
+        assert_eq!(withdrawn_shares(&decode_events(&logs)), [2]);
+    }
+
+    #[test]
+    fn decodes_in_kind_withdrawals() {
+        let id = crate::ID.to_string();
+        let mint = Pubkey::new_unique();
+        let logs = [
+            format!("Program {} invoke [1]", id),
+            data_log(
+                EVENT_VERSION,
+                YokoProgramEvent::WithdrawnInKind,
+                &WithdrawnInKind {
+                    mint,
+                    amount: 7,
+                    ..WithdrawnInKind::zeroed()
+                },
+            ),
+            data_log(
+                EVENT_VERSION,
+                YokoProgramEvent::Withdrawn,
+                &Withdrawn {
+                    main_amount: 9,
+                    in_kind_mints: 1,
+                    ..withdrawn(3)
+                },
+            ),
+            format!("Program {} success", id),
+        ];
+
+        let events = decode_events(&logs);
+        assert!(matches!(
+            events.as_slice(),
+            [Event::WithdrawnInKind(in_kind), Event::Withdrawn(withdrawn)]
+                if in_kind.mint == mint
+                    && in_kind.amount == 7
+                    && withdrawn.shares == 3
+                    && withdrawn.main_amount == 9
+                    && withdrawn.in_kind_mints == 1
+        ));
+    }
+}
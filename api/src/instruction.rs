@@ -10,17 +10,50 @@ pub enum YokoProgramInstruction {
     ClaimPayout = 4,
     Swap = 5,
     CreateFundTokenAccount = 6,
+    Withdraw = 7,
+    ClaimPayouts = 8,
+    ProposeAuthority = 9,
+    AcceptAuthority = 10,
+    InitializeProtocol = 11,
+    UpdateProtocol = 12,
+    AccrueManagementFee = 13,
+    SetSwapProgram = 14,
+    CreateMandate = 15,
+    SetRiskLimits = 16,
+    SetDepositLimits = 17,
+    SetAccessPolicy = 18,
+    AddToAllowlist = 19,
+    RemoveFromAllowlist = 20,
+    SetRedemptionRules = 21,
+    RequestRedemption = 22,
+    SetFundPaused = 23,
+    DepositSol = 24,
+    ClaimPayoutSol = 25,
+    DepositInKind = 26,
+    CreatePayoutInKind = 27,
+    ClosePayout = 28,
+    ReclaimPayout = 29,
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct CreateFund {
-    pub authority_fee: [u8; 8],
+    pub id: [u8; 8],
+    pub authority_fee_bps: [u8; 8],
+    pub hurdle_bps: [u8; 8],
+    pub management_fee_bps: [u8; 8],
+    pub max_swap_deviation_bps: [u8; 8],
+    pub deposit_cap: [u8; 8],
+    pub min_deposit: [u8; 8],
+    pub max_position_deposit: [u8; 8],
+    pub fee_mode: u8,
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct CreatePosition {}
+// data unpacked in the processor:
+// 1. merkle proof, 32 bytes per node
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
@@ -32,6 +65,7 @@ pub struct Deposit {
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct CreatePayout {
     pub amount: [u8; 8],
+    pub claim_period: [u8; 8],
 }
 
 #[repr(C)]
@@ -43,12 +77,146 @@ pub struct ClaimPayout {}
 pub struct Swap {}
 // data unpacked in the processor:
 // 1. in_amount
-// 2. jupiter_route_cpi_data
+// 2. min_out_amount
+// 3. jupiter_route_cpi_data
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct CreateFundTokenAccount {}
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Withdraw {
+    pub shares: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ClaimPayouts {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ProposeAuthority {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AcceptAuthority {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct InitializeProtocol {
+    pub fee_recipient: Pubkey,
+    pub protocol_fee_bps: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct UpdateProtocol {
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub protocol_fee_bps: [u8; 8],
+    pub paused: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AccrueManagementFee {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SetSwapProgram {
+    pub program: Pubkey,
+    pub allowed: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct CreateMandate {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SetRiskLimits {
+    pub max_concentration_bps: [u8; 8],
+    pub max_trade_bps: [u8; 8],
+    pub min_swap_interval: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SetDepositLimits {
+    pub deposit_cap: [u8; 8],
+    pub min_deposit: [u8; 8],
+    pub max_position_deposit: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SetAccessPolicy {
+    pub merkle_root: [u8; 32],
+    pub gate_mint: Pubkey,
+    pub access_mode: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AddToAllowlist {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct RemoveFromAllowlist {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SetRedemptionRules {
+    pub lockup_period: [u8; 8],
+    pub notice_period: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct RequestRedemption {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SetFundPaused {
+    pub paused: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct DepositSol {
+    pub amount: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ClaimPayoutSol {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct DepositInKind {
+    pub amount: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct CreatePayoutInKind {
+    pub amount: [u8; 8],
+    pub claim_period: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ClosePayout {
+    pub counter: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ReclaimPayout {
+    pub counter: [u8; 8],
+}
+
 instruction!(YokoProgramInstruction, CreateFund);
 instruction!(YokoProgramInstruction, CreatePosition);
 instruction!(YokoProgramInstruction, Deposit);
@@ -56,3 +224,26 @@ instruction!(YokoProgramInstruction, CreatePayout);
 instruction!(YokoProgramInstruction, ClaimPayout);
 instruction!(YokoProgramInstruction, Swap);
 instruction!(YokoProgramInstruction, CreateFundTokenAccount);
+instruction!(YokoProgramInstruction, Withdraw);
+instruction!(YokoProgramInstruction, ClaimPayouts);
+instruction!(YokoProgramInstruction, ProposeAuthority);
+instruction!(YokoProgramInstruction, AcceptAuthority);
+instruction!(YokoProgramInstruction, InitializeProtocol);
+instruction!(YokoProgramInstruction, UpdateProtocol);
+instruction!(YokoProgramInstruction, AccrueManagementFee);
+instruction!(YokoProgramInstruction, SetSwapProgram);
+instruction!(YokoProgramInstruction, CreateMandate);
+instruction!(YokoProgramInstruction, SetRiskLimits);
+instruction!(YokoProgramInstruction, SetDepositLimits);
+instruction!(YokoProgramInstruction, SetAccessPolicy);
+instruction!(YokoProgramInstruction, AddToAllowlist);
+instruction!(YokoProgramInstruction, RemoveFromAllowlist);
+instruction!(YokoProgramInstruction, SetRedemptionRules);
+instruction!(YokoProgramInstruction, RequestRedemption);
+instruction!(YokoProgramInstruction, SetFundPaused);
+instruction!(YokoProgramInstruction, DepositSol);
+instruction!(YokoProgramInstruction, ClaimPayoutSol);
+instruction!(YokoProgramInstruction, DepositInKind);
+instruction!(YokoProgramInstruction, CreatePayoutInKind);
+instruction!(YokoProgramInstruction, ClosePayout);
+instruction!(YokoProgramInstruction, ReclaimPayout);
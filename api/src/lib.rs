@@ -1,15 +1,28 @@
 pub mod consts;
 pub mod error;
+pub mod event;
 pub mod instruction;
+pub mod math;
+pub mod merkle;
+pub mod oracle;
 pub mod sdk;
 pub mod state;
+pub mod token;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 pub mod prelude {
     pub use crate::consts::*;
     pub use crate::error::*;
+    pub use crate::event::*;
     pub use crate::instruction::*;
+    pub use crate::math::*;
+    pub use crate::merkle::*;
+    pub use crate::oracle::*;
     pub use crate::sdk::*;
     pub use crate::state::*; 
+    pub use crate::token::*;
 }
 
 use steel::*;
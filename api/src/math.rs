@@ -0,0 +1,133 @@
+use steel::*;
+
+use crate::consts::{MAX_BPS, NAV_PER_SHARE_SCALE};
+
+/// `bps` basis points of `amount`, rounded down.
+///
+/// Fees are always rounded down so that any remainder stays with the depositors.
+pub fn bps_of(amount: u64, bps: u64) -> Result<u64, ProgramError> {
+    let fee = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        / MAX_BPS as u128;
+
+    u64::try_from(fee).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+/// Splits a payout of `amount` into (protocol fee, authority fee, rest). The protocol fee is
+/// `protocol_fee_bps` of `amount` and the authority fee is capped at what the protocol fee leaves,
+/// so the three always add up to `amount`.
+pub fn split_payout(
+    amount: u64,
+    protocol_fee_bps: u64,
+    authority_fee: u64,
+) -> Result<(u64, u64, u64), ProgramError> {
+    let protocol_fee = bps_of(amount, protocol_fee_bps)?;
+    let after_protocol_fee = amount
+        .checked_sub(protocol_fee)
+        .ok_or(ProgramError::InvalidArgument)?;
+    let authority_fee = authority_fee.min(after_protocol_fee);
+
+    Ok((
+        protocol_fee,
+        authority_fee,
+        after_protocol_fee - authority_fee,
+    ))
+}
+
+/// Net asset value per share in main mint base units, scaled by `NAV_PER_SHARE_SCALE` and rounded
+/// down.
+pub fn nav_per_share(value: u64, total_shares: u64) -> Result<u64, ProgramError> {
+    let nav_per_share = (value as u128)
+        .checked_mul(NAV_PER_SHARE_SCALE as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .checked_div(total_shares as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    u64::try_from(nav_per_share).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+/// `amount * shares / total_shares`, rounded down.
+pub fn pro_rata(amount: u64, shares: u64, total_shares: u64) -> Result<u64, ProgramError> {
+    let amount = (amount as u128)
+        .checked_mul(shares as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .checked_div(total_shares as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    u64::try_from(amount).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::{MAX_AUTHORITY_FEE_BPS, MAX_PROTOCOL_FEE_BPS};
+
+    #[test]
+    fn bps_of_rounds_down() {
+        assert_eq!(bps_of(9_999, 1), Ok(0));
+        assert_eq!(bps_of(10_000, 1), Ok(1));
+        assert_eq!(bps_of(19_999, 1), Ok(1));
+        assert_eq!(bps_of(1_000, 2_500), Ok(250));
+        assert_eq!(bps_of(1_001, 2_500), Ok(250));
+        assert_eq!(bps_of(u64::MAX, MAX_BPS), Ok(u64::MAX));
+        assert_eq!(bps_of(u64::MAX, 0), Ok(0));
+    }
+
+    #[test]
+    fn bps_of_above_max_bps_can_overflow() {
+        assert_eq!(
+            bps_of(u64::MAX, MAX_BPS + 1),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn max_fees_fit_in_a_payout() {
+        assert_eq!(MAX_AUTHORITY_FEE_BPS + MAX_PROTOCOL_FEE_BPS, MAX_BPS);
+
+        for amount in [0, 1, 9_999, 10_001, 123_456_789, u64::MAX] {
+            let authority_fee = bps_of(amount, MAX_AUTHORITY_FEE_BPS).unwrap();
+            let (protocol_fee, charged_authority_fee, rest) =
+                split_payout(amount, MAX_PROTOCOL_FEE_BPS, authority_fee).unwrap();
+
+            // Rounding down both fees never lets them exceed the payout.
+            assert_eq!(charged_authority_fee, authority_fee);
+            assert_eq!(protocol_fee + charged_authority_fee + rest, amount);
+        }
+    }
+
+    #[test]
+    fn split_payout_adds_up() {
+        for amount in [0, 1, 3, 10_000, 987_654_321, u64::MAX] {
+            for protocol_fee_bps in [0, 1, 333, MAX_PROTOCOL_FEE_BPS] {
+                for authority_fee_bps in [0, 1, 2_000, MAX_AUTHORITY_FEE_BPS] {
+                    let authority_fee = bps_of(amount, authority_fee_bps).unwrap();
+                    let (protocol_fee, authority_fee, rest) =
+                        split_payout(amount, protocol_fee_bps, authority_fee).unwrap();
+
+                    assert_eq!(protocol_fee, bps_of(amount, protocol_fee_bps).unwrap());
+                    assert_eq!(
+                        protocol_fee as u128 + authority_fee as u128 + rest as u128,
+                        amount as u128
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn split_payout_caps_the_authority_fee() {
+        // A performance fee can be worth more than the payout after the protocol fee.
+        assert_eq!(split_payout(1_000, 1_000, 5_000), Ok((100, 900, 0)));
+        assert_eq!(split_payout(1_000, 1_000, 899), Ok((100, 899, 1)));
+    }
+
+    #[test]
+    fn split_payout_rejects_protocol_fee_above_max_bps() {
+        assert_eq!(
+            split_payout(10_000, MAX_BPS + 1, 0),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
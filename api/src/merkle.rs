@@ -0,0 +1,151 @@
+use solana_program::hash::hashv;
+use steel::*;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf of `depositor` in a fund's depositor Merkle tree. Leaves and inner nodes are hashed with
+/// different prefixes so that an inner node cannot be passed off as a leaf.
+pub fn merkle_leaf(depositor: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, depositor.as_ref()]).to_bytes()
+}
+
+/// Hashes two nodes in sorted order, so proofs don't need to say which side a sibling is on.
+fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+pub fn verify_merkle_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| merkle_node(&node, sibling))
+        == *root
+}
+
+/// Root of the tree over `depositors`. The order of `depositors` doesn't matter. A node without a
+/// sibling is carried up to the next level unchanged.
+pub fn merkle_root(depositors: &[Pubkey]) -> [u8; 32] {
+    let mut level = merkle_leaves(depositors);
+    if level.is_empty() {
+        return [0; 32];
+    }
+
+    while level.len() > 1 {
+        level = next_merkle_level(&level);
+    }
+
+    level[0]
+}
+
+/// Proof of `depositor` against `merkle_root(depositors)`, or `None` if it is not one of them.
+pub fn merkle_proof(depositors: &[Pubkey], depositor: &Pubkey) -> Option<Vec<[u8; 32]>> {
+    let mut level = merkle_leaves(depositors);
+    let mut index = level.binary_search(&merkle_leaf(depositor)).ok()?;
+    let mut proof = vec![];
+
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_merkle_level(&level);
+        index /= 2;
+    }
+
+    Some(proof)
+}
+
+fn merkle_leaves(depositors: &[Pubkey]) -> Vec<[u8; 32]> {
+    let mut leaves: Vec<[u8; 32]> = depositors.iter().map(merkle_leaf).collect();
+    leaves.sort_unstable();
+    leaves.dedup();
+    leaves
+}
+
+fn next_merkle_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => merkle_node(left, right),
+            [node] => *node,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depositors(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn every_depositor_has_a_valid_proof() {
+        for count in 1..=9 {
+            let depositors = depositors(count);
+            let root = merkle_root(&depositors);
+
+            for depositor in &depositors {
+                let proof = merkle_proof(&depositors, depositor).unwrap();
+                assert!(verify_merkle_proof(&root, merkle_leaf(depositor), &proof));
+            }
+        }
+    }
+
+    #[test]
+    fn single_depositor_root_is_its_leaf() {
+        let depositors = depositors(1);
+
+        assert_eq!(merkle_root(&depositors), merkle_leaf(&depositors[0]));
+        assert_eq!(merkle_proof(&depositors, &depositors[0]), Some(vec![]));
+    }
+
+    #[test]
+    fn empty_tree_has_a_zero_root() {
+        assert_eq!(merkle_root(&[]), [0; 32]);
+        assert_eq!(merkle_proof(&[], &Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn root_ignores_order_and_duplicates() {
+        let depositors = depositors(5);
+        let mut shuffled = depositors.clone();
+        shuffled.reverse();
+        shuffled.push(depositors[2]);
+
+        assert_eq!(merkle_root(&depositors), merkle_root(&shuffled));
+    }
+
+    #[test]
+    fn other_depositors_are_rejected() {
+        let depositors = depositors(4);
+        let root = merkle_root(&depositors);
+        let outsider = Pubkey::new_unique();
+
+        assert_eq!(merkle_proof(&depositors, &outsider), None);
+
+        let proof = merkle_proof(&depositors, &depositors[0]).unwrap();
+        assert!(!verify_merkle_proof(&root, merkle_leaf(&outsider), &proof));
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let depositors = depositors(6);
+        let root = merkle_root(&depositors);
+        let leaf = merkle_leaf(&depositors[3]);
+        let proof = merkle_proof(&depositors, &depositors[3]).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered[0][0] ^= 1;
+        assert!(!verify_merkle_proof(&root, leaf, &tampered));
+
+        assert!(!verify_merkle_proof(&root, leaf, &proof[..proof.len() - 1]));
+        assert!(!verify_merkle_proof(
+            &merkle_root(&depositors[1..]),
+            leaf,
+            &proof
+        ));
+    }
+}
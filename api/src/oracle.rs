@@ -0,0 +1,343 @@
+use solana_program::pubkey;
+use steel::*;
+
+use crate::{consts::MAX_BPS, error::YokoProgramError};
+
+/// A positive price with a base 10 exponent, e.g. 150_000_000 * 10^-6.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub exponent: i32,
+}
+
+/// Reads prices out of oracle accounts. Implemented once per supported oracle layout.
+pub trait PriceFeed {
+    /// The program that owns the price accounts.
+    fn owner() -> Pubkey;
+
+    /// Parses the id of the price feed stored in `data`.
+    fn parse_feed_id(data: &[u8]) -> Result<[u8; 32], ProgramError>;
+
+    /// Parses the price stored in `data`, rejecting it if it is not for `feed_id` or was published
+    /// more than `max_age` seconds before `now`.
+    fn parse_price(
+        data: &[u8],
+        feed_id: &[u8; 32],
+        now: i64,
+        max_age: i64,
+    ) -> Result<OraclePrice, ProgramError>;
+
+    fn load_feed_id(info: &AccountInfo) -> Result<[u8; 32], ProgramError> {
+        info.has_owner(&Self::owner())?;
+        Self::parse_feed_id(&info.data.borrow())
+    }
+
+    fn load_price(
+        info: &AccountInfo,
+        feed_id: &[u8; 32],
+        now: i64,
+        max_age: i64,
+    ) -> Result<OraclePrice, ProgramError> {
+        info.has_owner(&Self::owner())?;
+        Self::parse_price(&info.data.borrow(), feed_id, now, max_age)
+    }
+}
+
+/// Pyth pull oracle `PriceUpdateV2` accounts.
+pub struct PythPriceUpdate;
+
+impl PythPriceUpdate {
+    pub(crate) const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+    // discriminator (8) | write_authority (32) | verification_level (1 byte when fully verified)
+    const FULLY_VERIFIED_TAG_OFFSET: usize = 40;
+    const MESSAGE_OFFSET: usize = 41;
+}
+
+impl PriceFeed for PythPriceUpdate {
+    fn owner() -> Pubkey {
+        pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ")
+    }
+
+    fn parse_feed_id(data: &[u8]) -> Result<[u8; 32], ProgramError> {
+        if data.len() < Self::MESSAGE_OFFSET + 60 || data[..8] != Self::DISCRIMINATOR {
+            return Err(YokoProgramError::InvalidPrice.into());
+        }
+
+        // Only accept updates with a full set of guardian signatures.
+        if data[Self::FULLY_VERIFIED_TAG_OFFSET] != 1 {
+            return Err(YokoProgramError::InvalidPrice.into());
+        }
+
+        Ok(data[Self::MESSAGE_OFFSET..Self::MESSAGE_OFFSET + 32]
+            .try_into()
+            .unwrap())
+    }
+
+    fn parse_price(
+        data: &[u8],
+        feed_id: &[u8; 32],
+        now: i64,
+        max_age: i64,
+    ) -> Result<OraclePrice, ProgramError> {
+        // The account of a price update can be overwritten with an update of another feed.
+        if Self::parse_feed_id(data)? != *feed_id {
+            return Err(YokoProgramError::PriceFeedMismatch.into());
+        }
+
+        // feed_id (32) | price (8) | conf (8) | exponent (4) | publish_time (8)
+        let message = &data[Self::MESSAGE_OFFSET + 32..];
+        let price = i64::from_le_bytes(message[0..8].try_into().unwrap());
+        let exponent = i32::from_le_bytes(message[16..20].try_into().unwrap());
+        let publish_time = i64::from_le_bytes(message[20..28].try_into().unwrap());
+
+        if price <= 0 {
+            return Err(YokoProgramError::InvalidPrice.into());
+        }
+
+        if now.saturating_sub(publish_time) > max_age {
+            return Err(YokoProgramError::StalePrice.into());
+        }
+
+        Ok(OraclePrice {
+            price: price as u64,
+            exponent,
+        })
+    }
+}
+
+/// Converts `amount` base units of a mint with `from_decimals` priced at `from_price`
+/// into base units of a mint with `to_decimals` priced at `to_price`, rounding down.
+pub fn convert_amount(
+    amount: u64,
+    from_decimals: u8,
+    from_price: &OraclePrice,
+    to_decimals: u8,
+    to_price: &OraclePrice,
+) -> Result<u64, ProgramError> {
+    let scale = from_price.exponent - to_price.exponent + to_decimals as i32 - from_decimals as i32;
+
+    let numerator = (amount as u128)
+        .checked_mul(from_price.price as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let converted = if scale >= 0 {
+        numerator
+            .checked_mul(
+                10u128
+                    .checked_pow(scale as u32)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            )
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .checked_div(to_price.price as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        let denominator = (to_price.price as u128)
+            .checked_mul(
+                10u128
+                    .checked_pow(scale.unsigned_abs())
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            )
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        numerator / denominator
+    };
+
+    u64::try_from(converted).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+/// Fails when `out_amount` is worth less than `in_amount` at oracle prices by more than
+/// `tolerance_bps`. Trades better than the oracle price always pass.
+pub fn check_swap_price(
+    in_amount: u64,
+    in_decimals: u8,
+    in_price: &OraclePrice,
+    out_amount: u64,
+    out_decimals: u8,
+    out_price: &OraclePrice,
+    tolerance_bps: u64,
+) -> Result<(), ProgramError> {
+    let expected_out_amount =
+        convert_amount(in_amount, in_decimals, in_price, out_decimals, out_price)?;
+
+    let min_out_amount = (expected_out_amount as u128)
+        .checked_mul(MAX_BPS.saturating_sub(tolerance_bps) as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        / MAX_BPS as u128;
+
+    if (out_amount as u128) < min_out_amount {
+        return Err(YokoProgramError::PriceDeviation.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::price_update;
+
+    const FEED_ID: [u8; 32] = [7; 32];
+    const SOL_PRICE: OraclePrice = OraclePrice {
+        price: 150_000_000,
+        exponent: -6,
+    };
+    const USDC_PRICE: OraclePrice = OraclePrice {
+        price: 100_000_000,
+        exponent: -8,
+    };
+
+    fn error(error: YokoProgramError) -> ProgramError {
+        error.into()
+    }
+
+    #[test]
+    fn parses_fully_verified_price() {
+        let data = price_update(&[1], &FEED_ID, 150_000_000, 100);
+
+        assert_eq!(PythPriceUpdate::parse_feed_id(&data), Ok(FEED_ID));
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data, &FEED_ID, 160, 60),
+            Ok(SOL_PRICE)
+        );
+    }
+
+    #[test]
+    fn rejects_partially_verified_price() {
+        let data = price_update(&[0, 5], &FEED_ID, 150_000_000, 100);
+
+        assert_eq!(
+            PythPriceUpdate::parse_feed_id(&data),
+            Err(error(YokoProgramError::InvalidPrice))
+        );
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data, &FEED_ID, 100, 60),
+            Err(error(YokoProgramError::InvalidPrice))
+        );
+    }
+
+    #[test]
+    fn rejects_other_account_and_truncated_data() {
+        let mut data = price_update(&[1], &FEED_ID, 150_000_000, 100);
+        data[0] ^= 1;
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data, &FEED_ID, 100, 60),
+            Err(error(YokoProgramError::InvalidPrice))
+        );
+
+        let data = price_update(&[1], &FEED_ID, 150_000_000, 100);
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data[..100], &FEED_ID, 100, 60),
+            Err(error(YokoProgramError::InvalidPrice))
+        );
+    }
+
+    #[test]
+    fn rejects_other_feed() {
+        let data = price_update(&[1], &FEED_ID, 150_000_000, 100);
+
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data, &[8; 32], 100, 60),
+            Err(error(YokoProgramError::PriceFeedMismatch))
+        );
+    }
+
+    #[test]
+    fn rejects_stale_price() {
+        let data = price_update(&[1], &FEED_ID, 150_000_000, 100);
+
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data, &FEED_ID, 160, 60),
+            Ok(SOL_PRICE)
+        );
+        assert_eq!(
+            PythPriceUpdate::parse_price(&data, &FEED_ID, 161, 60),
+            Err(error(YokoProgramError::StalePrice))
+        );
+    }
+
+    #[test]
+    fn rejects_non_positive_price() {
+        for price in [0, -1] {
+            let data = price_update(&[1], &FEED_ID, price, 100);
+
+            assert_eq!(
+                PythPriceUpdate::parse_price(&data, &FEED_ID, 100, 60),
+                Err(error(YokoProgramError::InvalidPrice))
+            );
+        }
+    }
+
+    #[test]
+    fn converts_with_negative_scale() {
+        // 1 SOL (9 decimals) at $150 is 150 USDC (6 decimals).
+        assert_eq!(
+            convert_amount(1_000_000_000, 9, &SOL_PRICE, 6, &USDC_PRICE),
+            Ok(150_000_000)
+        );
+        // Rounds down.
+        assert_eq!(convert_amount(6, 9, &SOL_PRICE, 6, &USDC_PRICE), Ok(0));
+        assert_eq!(convert_amount(7, 9, &SOL_PRICE, 6, &USDC_PRICE), Ok(1));
+    }
+
+    #[test]
+    fn converts_with_positive_scale() {
+        // 150 USDC (6 decimals) is 1 SOL (9 decimals) at $150.
+        assert_eq!(
+            convert_amount(150_000_000, 6, &USDC_PRICE, 9, &SOL_PRICE),
+            Ok(1_000_000_000)
+        );
+        // Rounds down.
+        assert_eq!(convert_amount(1, 6, &USDC_PRICE, 9, &SOL_PRICE), Ok(6));
+    }
+
+    #[test]
+    fn conversion_overflow_is_an_error() {
+        let price = OraclePrice {
+            price: 1,
+            exponent: 0,
+        };
+
+        assert_eq!(
+            convert_amount(u64::MAX, 0, &price, 1, &price),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn swap_price_within_tolerance_passes() {
+        // Selling 1 SOL at $150 with a 1% tolerance needs at least 148.5 USDC.
+        assert_eq!(
+            check_swap_price(
+                1_000_000_000,
+                9,
+                &SOL_PRICE,
+                148_500_000,
+                6,
+                &USDC_PRICE,
+                100
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_swap_price(
+                1_000_000_000,
+                9,
+                &SOL_PRICE,
+                148_499_999,
+                6,
+                &USDC_PRICE,
+                100
+            ),
+            Err(error(YokoProgramError::PriceDeviation))
+        );
+    }
+
+    #[test]
+    fn swap_price_better_than_oracle_passes() {
+        assert_eq!(
+            check_swap_price(1_000_000_000, 9, &SOL_PRICE, 200_000_000, 6, &USDC_PRICE, 0),
+            Ok(())
+        );
+    }
+}
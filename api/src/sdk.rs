@@ -2,12 +2,23 @@ use steel::*;
 
 use crate::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_fund(
     fund: Pubkey,
     authority: Pubkey,
     main_mint: Pubkey,
     main_token_account: Pubkey,
-    authority_fee: u64,
+    main_oracle: Pubkey,
+    token_program: Pubkey,
+    id: u64,
+    authority_fee_bps: u64,
+    fee_mode: FeeMode,
+    hurdle_bps: u64,
+    management_fee_bps: u64,
+    max_swap_deviation_bps: u64,
+    deposit_cap: u64,
+    min_deposit: u64,
+    max_position_deposit: u64,
 ) -> Instruction {
     Instruction {
         program_id: crate::ID,
@@ -16,35 +27,100 @@ pub fn create_fund(
             AccountMeta::new(authority, true),
             AccountMeta::new_readonly(main_mint, false),
             AccountMeta::new(main_token_account, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(main_oracle, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(token_program, false),
             AccountMeta::new_readonly(solana_program::system_program::ID, false),
         ],
         data: CreateFund {
-            authority_fee: authority_fee.to_le_bytes(),
+            id: id.to_le_bytes(),
+            authority_fee_bps: authority_fee_bps.to_le_bytes(),
+            hurdle_bps: hurdle_bps.to_le_bytes(),
+            management_fee_bps: management_fee_bps.to_le_bytes(),
+            max_swap_deviation_bps: max_swap_deviation_bps.to_le_bytes(),
+            deposit_cap: deposit_cap.to_le_bytes(),
+            min_deposit: min_deposit.to_le_bytes(),
+            max_position_deposit: max_position_deposit.to_le_bytes(),
+            fee_mode: fee_mode.into(),
         }
         .to_bytes(),
     }
 }
 
-pub fn create_position(position: Pubkey, fund: Pubkey, authority: Pubkey) -> Instruction {
+/// `access_account` is the `AllowlistEntry` for `AccessMode::Allowlist` or a token account of the
+/// gate mint owned by `authority` for `AccessMode::TokenGate`. `merkle_proof` is only needed for
+/// `AccessMode::Merkle`.
+pub fn create_position(
+    position: Pubkey,
+    fund: Pubkey,
+    authority: Pubkey,
+    access_account: Option<Pubkey>,
+    merkle_proof: Vec<[u8; 32]>,
+) -> Instruction {
     Instruction {
         program_id: crate::ID,
         accounts: vec![
             AccountMeta::new(position, false),
             AccountMeta::new_readonly(fund, false),
             AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
             AccountMeta::new_readonly(solana_program::system_program::ID, false),
-        ],
-        data: CreatePosition {}.to_bytes(),
+        ]
+        .into_iter()
+        .chain(
+            access_account.map(|access_account| AccountMeta::new_readonly(access_account, false)),
+        )
+        .collect(),
+        data: [CreatePosition {}.to_bytes(), merkle_proof.concat()].concat(),
+    }
+}
+
+/// Accounts the program needs to value a fund: empty if the fund only holds its main mint,
+/// otherwise the main mint oracle followed by (token account, oracle) for every other mint.
+pub fn fund_valuation_accounts(fund: &Pubkey, fund_data: &Fund) -> Vec<AccountMeta> {
+    if fund_data.other_mints.is_empty() {
+        return vec![];
     }
+
+    let mut accounts = vec![AccountMeta::new_readonly(fund_data.main_oracle, false)];
+    for (mint, oracle, _, _) in fund_data.other_assets() {
+        accounts.push(AccountMeta::new_readonly(
+            fund_token_account_pda(fund, mint).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*oracle, false));
+    }
+    accounts
+}
+
+/// Accounts `Swap` needs to check the concentration limit: empty when the fund has no limit,
+/// otherwise the fund main token account followed by `fund_valuation_accounts`.
+///
+/// `fund_data` must already include a destination mint that is added in the same transaction.
+pub fn swap_valuation_accounts(fund: &Pubkey, fund_data: &Fund) -> Vec<AccountMeta> {
+    if fund_data.max_concentration_bps == 0 {
+        return vec![];
+    }
+
+    let mut accounts = vec![AccountMeta::new_readonly(
+        fund_token_account_pda(fund, &fund_data.main_mint).0,
+        false,
+    )];
+    accounts.extend(fund_valuation_accounts(fund, fund_data));
+    accounts
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn deposit(
     position: Pubkey,
     fund: Pubkey,
     fund_main_token_account: Pubkey,
     depositor_authority: Pubkey,
     depositor_token_account: Pubkey,
+    main_mint: Pubkey,
+    token_program: Pubkey,
+    fund_authority_token_account: Pubkey,
+    valuation_accounts: Vec<AccountMeta>,
     amount: u64,
 ) -> Instruction {
     Instruction {
@@ -55,8 +131,14 @@ pub fn deposit(
             AccountMeta::new(fund_main_token_account, false),
             AccountMeta::new(depositor_authority, true),
             AccountMeta::new(depositor_token_account, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
-        ],
+            AccountMeta::new_readonly(main_mint, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(fund_authority_token_account, false),
+        ]
+        .into_iter()
+        .chain(valuation_accounts)
+        .collect(),
         data: Deposit {
             amount: amount.to_le_bytes(),
         }
@@ -64,6 +146,7 @@ pub fn deposit(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_payout(
     fund_authority: Pubkey,
     fund_authority_token_account: Pubkey,
@@ -73,7 +156,10 @@ pub fn create_payout(
     payout_main_token_account: Pubkey,
     main_mint: Pubkey,
     protocol_fee_token_account: Pubkey,
+    token_program: Pubkey,
+    valuation_accounts: Vec<AccountMeta>,
     amount: u64,
+    claim_period: i64,
 ) -> Instruction {
     Instruction {
         program_id: crate::ID,
@@ -85,24 +171,32 @@ pub fn create_payout(
             AccountMeta::new(payout, false),
             AccountMeta::new(payout_main_token_account, false),
             AccountMeta::new_readonly(main_mint, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
             AccountMeta::new(protocol_fee_token_account, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(token_program, false),
             AccountMeta::new_readonly(solana_program::system_program::ID, false),
-        ],
+        ]
+        .into_iter()
+        .chain(valuation_accounts)
+        .collect(),
         data: CreatePayout {
             amount: amount.to_le_bytes(),
+            claim_period: claim_period.to_le_bytes(),
         }
         .to_bytes(),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn claim_payout(
     position: Pubkey,
     position_authority: Pubkey,
     payout: Pubkey,
-    payout_main_token_account: Pubkey,
-    depositor_main_token_account: Pubkey,
+    payout_token_account: Pubkey,
+    depositor_token_account: Pubkey,
     fund: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
 ) -> Instruction {
     Instruction {
         program_id: crate::ID,
@@ -110,27 +204,38 @@ pub fn claim_payout(
             AccountMeta::new(position, false),
             AccountMeta::new(position_authority, true),
             AccountMeta::new(payout, false),
-            AccountMeta::new(payout_main_token_account, false),
-            AccountMeta::new(depositor_main_token_account, false),
+            AccountMeta::new(payout_token_account, false),
+            AccountMeta::new(depositor_token_account, false),
             AccountMeta::new_readonly(fund, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(token_program, false),
         ],
         data: ClaimPayout {}.to_bytes(),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn swap(
     fund_authority: Pubkey,
     fund: Pubkey,
     fund_source_token_account: Pubkey,
     fund_destination_token_account: Pubkey,
-    jupiter_program: Pubkey,
+    source_oracle: Pubkey,
+    destination_oracle: Pubkey,
+    swap_program: Pubkey,
+    source_mint: Pubkey,
+    destination_mint: Pubkey,
+    source_token_program: Pubkey,
+    destination_token_program: Pubkey,
+    valuation_accounts: Vec<AccountMeta>,
     jupiter_accounts_metas: Vec<AccountMeta>,
     in_amount: u64,
+    min_out_amount: u64,
     jupiter_route_cpi_data: &[u8],
 ) -> Instruction {
     let mut data = vec![5]; // instruction discriminator
     data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&min_out_amount.to_le_bytes());
     data.extend_from_slice(jupiter_route_cpi_data);
 
     Instruction {
@@ -140,9 +245,18 @@ pub fn swap(
             AccountMeta::new(fund, false),
             AccountMeta::new(fund_destination_token_account, false),
             AccountMeta::new(fund_source_token_account, false),
-            AccountMeta::new_readonly(jupiter_program, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(mandate_pda(&fund).0, false),
+            AccountMeta::new_readonly(source_oracle, false),
+            AccountMeta::new_readonly(destination_oracle, false),
+            AccountMeta::new_readonly(swap_program, false),
+            AccountMeta::new_readonly(source_mint, false),
+            AccountMeta::new_readonly(destination_mint, false),
+            AccountMeta::new_readonly(source_token_program, false),
+            AccountMeta::new_readonly(destination_token_program, false),
         ]
         .into_iter()
+        .chain(valuation_accounts)
         .chain(jupiter_accounts_metas)
         .collect(),
         data,
@@ -154,6 +268,8 @@ pub fn create_fund_token_account(
     fund_authority: Pubkey,
     fund_token_account: Pubkey,
     mint: Pubkey,
+    oracle: Pubkey,
+    token_program: Pubkey,
 ) -> Instruction {
     Instruction {
         program_id: crate::ID,
@@ -162,9 +278,593 @@ pub fn create_fund_token_account(
             AccountMeta::new(fund_authority, true),
             AccountMeta::new(fund_token_account, false),
             AccountMeta::new_readonly(mint, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(oracle, false),
+            AccountMeta::new_readonly(mandate_pda(&fund).0, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(token_program, false),
             AccountMeta::new_readonly(solana_program::system_program::ID, false),
         ],
         data: CreateFundTokenAccount {}.to_bytes(),
     }
 }
+
+/// `other_token_accounts` are (fund token account, depositor token account, mint, token program)
+/// groups, one for every mint in `Fund.other_mints` in order. `valuation_accounts` are only read,
+/// to collect the pending management fee, when the fund charges one.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    position: Pubkey,
+    position_authority: Pubkey,
+    fund: Pubkey,
+    fund_main_token_account: Pubkey,
+    depositor_main_token_account: Pubkey,
+    main_mint: Pubkey,
+    token_program: Pubkey,
+    fund_authority_token_account: Pubkey,
+    other_token_accounts: Vec<(Pubkey, Pubkey, Pubkey, Pubkey)>,
+    valuation_accounts: Vec<AccountMeta>,
+    shares: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(position, false),
+            AccountMeta::new(position_authority, true),
+            AccountMeta::new(fund, false),
+            AccountMeta::new(fund_main_token_account, false),
+            AccountMeta::new(depositor_main_token_account, false),
+            AccountMeta::new_readonly(main_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(fund_authority_token_account, false),
+        ]
+        .into_iter()
+        .chain(other_token_accounts.into_iter().flat_map(
+            |(fund_token_account, depositor_token_account, mint, token_program)| {
+                [
+                    AccountMeta::new(fund_token_account, false),
+                    AccountMeta::new(depositor_token_account, false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new_readonly(token_program, false),
+                ]
+            },
+        ))
+        .chain(valuation_accounts)
+        .collect(),
+        data: Withdraw {
+            shares: shares.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// `payouts` are (payout, payout main token account) pairs for consecutive payouts,
+/// starting right after `Position.payouts_counter`.
+pub fn claim_payouts(
+    position: Pubkey,
+    position_authority: Pubkey,
+    depositor_token_account: Pubkey,
+    fund: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    payouts: Vec<(Pubkey, Pubkey)>,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(position, false),
+            AccountMeta::new(position_authority, true),
+            AccountMeta::new(depositor_token_account, false),
+            AccountMeta::new_readonly(fund, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(token_program, false),
+        ]
+        .into_iter()
+        .chain(
+            payouts
+                .into_iter()
+                .flat_map(|(payout, payout_token_account)| {
+                    [
+                        AccountMeta::new(payout, false),
+                        AccountMeta::new(payout_token_account, false),
+                    ]
+                }),
+        )
+        .collect(),
+        data: ClaimPayouts {}.to_bytes(),
+    }
+}
+
+pub fn propose_authority(fund: Pubkey, authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(new_authority, false),
+        ],
+        data: ProposeAuthority {}.to_bytes(),
+    }
+}
+
+pub fn accept_authority(fund: Pubkey, new_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(new_authority, true),
+        ],
+        data: AcceptAuthority {}.to_bytes(),
+    }
+}
+
+pub fn initialize_protocol(
+    admin: Pubkey,
+    fee_recipient: Pubkey,
+    protocol_fee_bps: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(protocol_config_pda().0, false),
+            AccountMeta::new(admin, true),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        ],
+        data: InitializeProtocol {
+            fee_recipient,
+            protocol_fee_bps: protocol_fee_bps.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+pub fn update_protocol(
+    admin: Pubkey,
+    new_admin: Pubkey,
+    fee_recipient: Pubkey,
+    protocol_fee_bps: u64,
+    paused: bool,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(admin, true),
+        ],
+        data: UpdateProtocol {
+            admin: new_admin,
+            fee_recipient,
+            protocol_fee_bps: protocol_fee_bps.to_le_bytes(),
+            paused: paused as u8,
+        }
+        .to_bytes(),
+    }
+}
+
+pub fn accrue_management_fee(
+    fund: Pubkey,
+    fund_main_token_account: Pubkey,
+    fund_authority_token_account: Pubkey,
+    main_mint: Pubkey,
+    token_program: Pubkey,
+    valuation_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new(fund_main_token_account, false),
+            AccountMeta::new(fund_authority_token_account, false),
+            AccountMeta::new_readonly(main_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+        ]
+        .into_iter()
+        .chain(valuation_accounts)
+        .collect(),
+        data: AccrueManagementFee {}.to_bytes(),
+    }
+}
+
+pub fn set_swap_program(admin: Pubkey, program: Pubkey, allowed: bool) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(admin, true),
+        ],
+        data: SetSwapProgram {
+            program,
+            allowed: allowed as u8,
+        }
+        .to_bytes(),
+    }
+}
+
+/// Restricts the fund to `mints` besides its main mint. Only possible before the first deposit.
+pub fn create_mandate(fund: Pubkey, fund_authority: Pubkey, mints: Vec<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new(fund_authority, true),
+            AccountMeta::new(mandate_pda(&fund).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        ]
+        .into_iter()
+        .chain(
+            mints
+                .into_iter()
+                .map(|mint| AccountMeta::new_readonly(mint, false)),
+        )
+        .collect(),
+        data: CreateMandate {}.to_bytes(),
+    }
+}
+
+/// Limits can be tightened at any time, but only loosened while the fund has no depositors.
+pub fn set_risk_limits(
+    fund: Pubkey,
+    fund_authority: Pubkey,
+    max_concentration_bps: u64,
+    max_trade_bps: u64,
+    min_swap_interval: i64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(fund_authority, true),
+        ],
+        data: SetRiskLimits {
+            max_concentration_bps: max_concentration_bps.to_le_bytes(),
+            max_trade_bps: max_trade_bps.to_le_bytes(),
+            min_swap_interval: min_swap_interval.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+pub fn set_deposit_limits(
+    fund: Pubkey,
+    fund_authority: Pubkey,
+    deposit_cap: u64,
+    min_deposit: u64,
+    max_position_deposit: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(fund_authority, true),
+        ],
+        data: SetDepositLimits {
+            deposit_cap: deposit_cap.to_le_bytes(),
+            min_deposit: min_deposit.to_le_bytes(),
+            max_position_deposit: max_position_deposit.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Only affects positions opened afterwards.
+pub fn set_access_policy(
+    fund: Pubkey,
+    fund_authority: Pubkey,
+    access_mode: AccessMode,
+    merkle_root: [u8; 32],
+    gate_mint: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(fund_authority, true),
+        ],
+        data: SetAccessPolicy {
+            merkle_root,
+            gate_mint,
+            access_mode: access_mode.into(),
+        }
+        .to_bytes(),
+    }
+}
+
+pub fn add_to_allowlist(fund: Pubkey, fund_authority: Pubkey, depositor: Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(fund, false),
+            AccountMeta::new(fund_authority, true),
+            AccountMeta::new(allowlist_entry_pda(&fund, &depositor).0, false),
+            AccountMeta::new_readonly(depositor, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        ],
+        data: AddToAllowlist {}.to_bytes(),
+    }
+}
+
+pub fn remove_from_allowlist(
+    fund: Pubkey,
+    fund_authority: Pubkey,
+    depositor: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(fund, false),
+            AccountMeta::new(fund_authority, true),
+            AccountMeta::new(allowlist_entry_pda(&fund, &depositor).0, false),
+        ],
+        data: RemoveFromAllowlist {}.to_bytes(),
+    }
+}
+
+/// Rules can be relaxed at any time, but only made stricter while the fund has no depositors.
+pub fn set_redemption_rules(
+    fund: Pubkey,
+    fund_authority: Pubkey,
+    lockup_period: i64,
+    notice_period: i64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(fund_authority, true),
+        ],
+        data: SetRedemptionRules {
+            lockup_period: lockup_period.to_le_bytes(),
+            notice_period: notice_period.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Starts the fund notice period. A new request restarts it.
+pub fn request_redemption(position: Pubkey, position_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(position, false),
+            AccountMeta::new_readonly(position_authority, true),
+        ],
+        data: RequestRedemption {}.to_bytes(),
+    }
+}
+
+/// Pausing blocks deposits, swaps and payouts, but not claims or withdrawals.
+pub fn set_fund_paused(fund: Pubkey, fund_authority: Pubkey, paused: bool) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(fund_authority, true),
+        ],
+        data: SetFundPaused {
+            paused: paused.into(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Deposits `amount` lamports into a fund whose main mint is native SOL, without a WSOL token
+/// account of the depositor.
+pub fn deposit_sol(
+    position: Pubkey,
+    fund: Pubkey,
+    fund_main_token_account: Pubkey,
+    depositor_authority: Pubkey,
+    fund_authority_token_account: Pubkey,
+    valuation_accounts: Vec<AccountMeta>,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(position, false),
+            AccountMeta::new(fund, false),
+            AccountMeta::new(fund_main_token_account, false),
+            AccountMeta::new(depositor_authority, true),
+            AccountMeta::new(wsol_account_pda(&position).0, false),
+            AccountMeta::new_readonly(spl_token::native_mint::ID, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+            AccountMeta::new(fund_authority_token_account, false),
+        ]
+        .into_iter()
+        .chain(valuation_accounts)
+        .collect(),
+        data: DepositSol {
+            amount: amount.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Like `claim_payouts`, paying out lamports instead of WSOL. `payouts` are (payout, payout main
+/// token account) pairs for consecutive payouts, starting right after `Position.payouts_counter`.
+pub fn claim_payout_sol(
+    position: Pubkey,
+    position_authority: Pubkey,
+    fund: Pubkey,
+    payouts: Vec<(Pubkey, Pubkey)>,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(position, false),
+            AccountMeta::new(position_authority, true),
+            AccountMeta::new(wsol_account_pda(&position).0, false),
+            AccountMeta::new_readonly(fund, false),
+            AccountMeta::new_readonly(spl_token::native_mint::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        ]
+        .into_iter()
+        .chain(
+            payouts
+                .into_iter()
+                .flat_map(|(payout, payout_main_token_account)| {
+                    [
+                        AccountMeta::new(payout, false),
+                        AccountMeta::new(payout_main_token_account, false),
+                    ]
+                }),
+        )
+        .collect(),
+        data: ClaimPayoutSol {}.to_bytes(),
+    }
+}
+
+/// Deposits `amount` of `mint`, one of `Fund.other_mints`, credited at its oracle value in the
+/// main mint. `oracle` is the price account `Fund.asset` records for `mint`.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_in_kind(
+    position: Pubkey,
+    fund: Pubkey,
+    fund_data: &Fund,
+    depositor_authority: Pubkey,
+    depositor_token_account: Pubkey,
+    mint: Pubkey,
+    oracle: Pubkey,
+    token_program: Pubkey,
+    fund_authority_token_account: Pubkey,
+    main_token_program: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(position, false),
+            AccountMeta::new(fund, false),
+            AccountMeta::new(fund_token_account_pda(&fund, &fund_data.main_mint).0, false),
+            AccountMeta::new(depositor_authority, true),
+            AccountMeta::new(depositor_token_account, false),
+            AccountMeta::new(fund_token_account_pda(&fund, &mint).0, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(oracle, false),
+            AccountMeta::new_readonly(fund_data.main_oracle, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(fund_authority_token_account, false),
+            AccountMeta::new_readonly(fund_data.main_mint, false),
+            AccountMeta::new_readonly(main_token_program, false),
+        ]
+        .into_iter()
+        .chain(fund_valuation_accounts(&fund, fund_data))
+        .collect(),
+        data: DepositInKind {
+            amount: amount.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Pays out `amount` of `mint`, one of `Fund.other_mints`, from the fund's token account of that
+/// mint. The payout can be claimed for `claim_period` seconds, or indefinitely when it is zero.
+/// `oracle` is the price account `Fund.asset` records for `mint`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_payout_in_kind(
+    fund_authority: Pubkey,
+    fund_authority_token_account: Pubkey,
+    fund: Pubkey,
+    fund_data: &Fund,
+    mint: Pubkey,
+    oracle: Pubkey,
+    protocol_fee_token_account: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+    claim_period: i64,
+) -> Instruction {
+    let payout = payout_pda(&fund, fund_data.payouts_counter + 1).0;
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(fund_authority, true),
+            AccountMeta::new(fund_authority_token_account, false),
+            AccountMeta::new(fund, false),
+            AccountMeta::new_readonly(fund_token_account_pda(&fund, &fund_data.main_mint).0, false),
+            AccountMeta::new(fund_token_account_pda(&fund, &mint).0, false),
+            AccountMeta::new(payout, false),
+            AccountMeta::new(payout_token_account_pda(&payout).0, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(oracle, false),
+            AccountMeta::new_readonly(fund_data.main_oracle, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new(protocol_fee_token_account, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        ]
+        .into_iter()
+        .chain(fund_valuation_accounts(&fund, fund_data))
+        .collect(),
+        data: CreatePayoutInKind {
+            amount: amount.to_le_bytes(),
+            claim_period: claim_period.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Closes the payout numbered `counter` once every position has claimed it, returning the rent of
+/// its accounts to `rent_payer` and anything sent to it since to the fund's token account of its
+/// mint.
+pub fn close_payout(
+    fund: Pubkey,
+    counter: u64,
+    rent_payer: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+) -> Instruction {
+    let payout = payout_pda(&fund, counter).0;
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(payout, false),
+            AccountMeta::new(payout_token_account_pda(&payout).0, false),
+            AccountMeta::new(rent_payer, false),
+            AccountMeta::new_readonly(fund, false),
+            AccountMeta::new(fund_token_account_pda(&fund, &mint).0, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: ClosePayout {
+            counter: counter.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
+
+/// Sweeps what is left of the expired payout numbered `counter` back into the fund's token account
+/// of its mint and closes it, returning the rent of its accounts to `rent_payer`.
+pub fn reclaim_payout(
+    fund_authority: Pubkey,
+    fund: Pubkey,
+    counter: u64,
+    rent_payer: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+) -> Instruction {
+    let payout = payout_pda(&fund, counter).0;
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(fund_authority, true),
+            AccountMeta::new_readonly(fund, false),
+            AccountMeta::new(fund_token_account_pda(&fund, &mint).0, false),
+            AccountMeta::new(payout, false),
+            AccountMeta::new(payout_token_account_pda(&payout).0, false),
+            AccountMeta::new(rent_payer, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(protocol_config_pda().0, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: ReclaimPayout {
+            counter: counter.to_le_bytes(),
+        }
+        .to_bytes(),
+    }
+}
@@ -0,0 +1,15 @@
+use steel::*;
+
+use super::YokoProgramAccount;
+
+/// Allows `depositor` to open a position in a fund with `AccessMode::Allowlist`.
+/// Seeds = [ALLOWLIST, fund, depositor]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct AllowlistEntry {
+    pub fund: Pubkey,
+
+    pub depositor: Pubkey,
+}
+
+account!(YokoProgramAccount, AllowlistEntry);
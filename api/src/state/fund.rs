@@ -1,23 +1,118 @@
 use steel::*;
 
-use super::YokoProgramAccount;
+use super::{Position, YokoProgramAccount};
+use crate::{consts::*, math::*};
 
-/// Seeds = [FUND, authority]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum FeeMode {
+    /// The authority takes `authority_fee_bps` of every payout.
+    Payout = 0,
+
+    /// The authority takes `authority_fee_bps` of the gains above the high-water mark and hurdle,
+    /// charged when a payout is created.
+    Performance = 1,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum AccessMode {
+    /// Anyone may open a position.
+    Open = 0,
+
+    /// Only depositors with an `AllowlistEntry` may open a position.
+    Allowlist = 1,
+
+    /// Only depositors with a proof against `Fund.merkle_root` may open a position.
+    Merkle = 2,
+
+    /// Only holders of `Fund.gate_mint` may open a position.
+    TokenGate = 3,
+}
+
+/// Seeds = [FUND, creator, id]
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
 pub struct Fund {
     /// The authority of the fund. Can make swaps and create payouts for depositors.
     pub authority: Pubkey,
 
-    /// May be different from the main token account balance. It's purpose is to track each depositor's proportion of the fund.
-    pub total_deposited: u64,
+    /// Proposed new authority, which takes over once it accepts. Default pubkey when none is pending.
+    pub pending_authority: Pubkey,
+
+    /// The wallet that created the fund. Only used for the fund seeds.
+    pub creator: Pubkey,
+
+    /// Chosen by the creator so that one wallet can create several funds.
+    pub id: u64,
+
+    /// Total shares held by all positions. Deposits mint shares at the current net asset value per share.
+    pub total_shares: u64,
 
     /// Number of payouts created by fund authority.
     /// Seeds for payout account = [PAYOUT, fund pubkey, payouts_counter]
     pub payouts_counter: u64,
 
-    /// The fee that the authority takes from each payout.
-    pub authority_fee: u64,
+    /// The fee that the authority takes from each payout, in basis points. See `fee_mode`.
+    pub authority_fee_bps: u64,
+
+    /// Return over the high-water mark, in basis points, that has to be beaten before a
+    /// performance fee is charged.
+    pub hurdle_bps: u64,
+
+    /// Highest net asset value per share a performance fee was charged at, scaled by
+    /// `NAV_PER_SHARE_SCALE`. Lowered by every payout so that returned capital is not charged twice.
+    pub high_water_mark: u64,
+
+    /// Annual fee on the net asset value, in basis points. Accrues by the second.
+    pub management_fee_bps: u64,
+
+    /// Unix timestamp the management fee was last accrued at.
+    pub last_management_fee_accrual: i64,
+
+    /// How far, in basis points, a swap may fall short of the oracle price. Zero disables the check.
+    /// Fixed at creation so that a compromised authority key cannot loosen it.
+    pub max_swap_deviation_bps: u64,
+
+    /// Largest share of the fund value, in basis points, a single non-main mint may make up after a
+    /// swap into it. Zero disables the limit.
+    pub max_concentration_bps: u64,
+
+    /// Largest share of the source balance, in basis points, a single swap may sell. Zero disables
+    /// the limit.
+    pub max_trade_bps: u64,
+
+    /// Seconds that have to pass between two swaps. Zero disables the limit.
+    pub min_swap_interval: i64,
+
+    /// Unix timestamp of the last swap.
+    pub last_swap_at: i64,
+
+    /// Main mint base units deposited and not yet withdrawn. Withdrawals reduce it pro rata to the
+    /// shares burned.
+    pub total_deposited: u64,
+
+    /// Largest `total_deposited` deposits may bring the fund to. Zero disables the cap.
+    pub deposit_cap: u64,
+
+    /// Smallest amount, in main mint base units, a single deposit may be.
+    pub min_deposit: u64,
+
+    /// Largest `Position.deposited` a single depositor may reach. Zero disables the limit.
+    pub max_position_deposit: u64,
+
+    /// Seconds after a position's last deposit before it can be withdrawn. Zero disables the lockup.
+    pub lockup_period: i64,
+
+    /// Seconds between a depositor requesting a redemption and being able to withdraw. Zero means
+    /// withdrawals need no notice.
+    pub notice_period: i64,
+
+    /// Root of the Merkle tree of depositors allowed to open a position, see `merkle_leaf`.
+    pub merkle_root: [u8; 32],
+
+    /// Mint depositors have to hold to open a position.
+    pub gate_mint: Pubkey,
 
     /// The main mint that this fund holds. Usually WSOL or USDC.
     /// Seeds for token account = [TOKEN_ACCOUNT, fund pubkey, mint pubkey]
@@ -26,6 +121,208 @@ pub struct Fund {
     /// The other mints that this fund holds. Inserted and removed in swaps.
     /// Seeds for token account = [TOKEN_ACCOUNT, fund pubkey, mint pubkey]
     pub other_mints: ArraySet,
+
+    /// Price account of the main mint. Used to value other mints in main mint terms.
+    pub main_oracle: Pubkey,
+
+    /// Price accounts of the other mints, at the same index as the mint in `other_mints`.
+    pub other_oracles: [Pubkey; 96],
+
+    pub main_decimals: u8,
+
+    /// See `FeeMode`.
+    pub fee_mode: u8,
+
+    /// Non-zero when the fund restricted the mints it may hold with a `Mandate`.
+    pub has_mandate: u8,
+
+    /// See `AccessMode`.
+    pub access_mode: u8,
+
+    /// Non-zero while the authority paused deposits, swaps and payouts.
+    pub paused: u8,
+
+    pub _padding: [u8; 3],
+
+    /// Decimals of the other mints, at the same index as the mint in `other_mints`.
+    pub other_decimals: [u8; 96],
+
+    /// Pyth feed id that `main_oracle` must hold prices of.
+    pub main_feed_id: [u8; 32],
+
+    /// Pyth feed ids that `other_oracles` must hold prices of, at the same index as the mint in
+    /// `other_mints`.
+    pub other_feed_ids: [[u8; 32]; 96],
+}
+
+impl Fund {
+    pub fn insert_other_mint(
+        &mut self,
+        mint: Pubkey,
+        oracle: Pubkey,
+        feed_id: [u8; 32],
+        decimals: u8,
+    ) -> bool {
+        if !self.other_mints.insert(mint) {
+            return false;
+        }
+
+        let Ok(pos) = self.other_mints.binary_search(&mint) else {
+            return false;
+        };
+        let len = self.other_mints.len();
+        self.other_oracles.copy_within(pos..len - 1, pos + 1);
+        self.other_decimals.copy_within(pos..len - 1, pos + 1);
+        self.other_feed_ids.copy_within(pos..len - 1, pos + 1);
+        self.other_oracles[pos] = oracle;
+        self.other_decimals[pos] = decimals;
+        self.other_feed_ids[pos] = feed_id;
+        true
+    }
+
+    pub fn remove_other_mint(&mut self, mint: &Pubkey) -> bool {
+        let Ok(pos) = self.other_mints.binary_search(mint) else {
+            return false;
+        };
+
+        let len = self.other_mints.len();
+        self.other_mints.remove(mint);
+        self.other_oracles.copy_within(pos + 1..len, pos);
+        self.other_decimals.copy_within(pos + 1..len, pos);
+        self.other_feed_ids.copy_within(pos + 1..len, pos);
+        self.other_oracles[len - 1] = Pubkey::default();
+        self.other_decimals[len - 1] = 0;
+        self.other_feed_ids[len - 1] = [0; 32];
+        true
+    }
+
+    pub fn has_mandate(&self) -> bool {
+        self.has_mandate != 0
+    }
+
+    pub fn access_mode(&self) -> AccessMode {
+        AccessMode::try_from(self.access_mode).unwrap_or(AccessMode::Open)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
+    }
+
+    pub fn has_performance_fee(&self) -> bool {
+        self.fee_mode == FeeMode::Performance as u8
+    }
+
+    /// Net asset value per share above which a performance fee is charged.
+    pub fn hurdle_nav_per_share(&self) -> Result<u64, ProgramError> {
+        let hurdle = (self.high_water_mark as u128)
+            .checked_mul((MAX_BPS + self.hurdle_bps) as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / MAX_BPS as u128;
+
+        u64::try_from(hurdle).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+
+    /// Performance fee owed on the gains above the hurdle when the fund is worth `value` main mint
+    /// base units.
+    pub fn performance_fee(&self, value: u64) -> Result<u64, ProgramError> {
+        if self.total_shares == 0 {
+            return Ok(0);
+        }
+
+        let nav_per_share = nav_per_share(value, self.total_shares)?;
+        let hurdle = self.hurdle_nav_per_share()?;
+        if nav_per_share <= hurdle {
+            return Ok(0);
+        }
+
+        let gains = ((nav_per_share - hurdle) as u128)
+            .checked_mul(self.total_shares as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / NAV_PER_SHARE_SCALE as u128;
+
+        bps_of(
+            u64::try_from(gains).map_err(|_| ProgramError::ArithmeticOverflow)?,
+            self.authority_fee_bps,
+        )
+    }
+
+    /// Management fee accrued since the last accrual when the fund is worth `value` main mint base
+    /// units, rounded down.
+    pub fn pending_management_fee(&self, value: u64, now: i64) -> Result<u64, ProgramError> {
+        if self.total_shares == 0 {
+            return Ok(0);
+        }
+
+        let elapsed = now.saturating_sub(self.last_management_fee_accrual).max(0);
+        let fee = (value as u128)
+            .checked_mul(self.management_fee_bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .checked_mul(elapsed as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / (MAX_BPS as u128 * SECONDS_PER_YEAR as u128);
+
+        u64::try_from(fee).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+
+    /// Timestamp the management fee counts as accrued until once `amount` of the `fee` pending at
+    /// `now` is taken. Rounds down, so that the part of the fee not taken keeps accruing.
+    pub fn management_fee_accrued_until(
+        &self,
+        now: i64,
+        fee: u64,
+        amount: u64,
+    ) -> Result<i64, ProgramError> {
+        if amount >= fee {
+            return Ok(now);
+        }
+
+        let elapsed = now.saturating_sub(self.last_management_fee_accrual).max(0);
+        let paid_for = (elapsed as u128)
+            .checked_mul(amount as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / fee as u128;
+
+        Ok(self.last_management_fee_accrual + paid_for as i64)
+    }
+
+    /// Main mint base units `position` may still deposit before reaching the fund cap or the
+    /// per-position limit, or `None` when neither is set.
+    pub fn remaining_deposit_capacity(&self, position: &Position) -> Option<u64> {
+        let fund_capacity =
+            (self.deposit_cap != 0).then(|| self.deposit_cap.saturating_sub(self.total_deposited));
+        let position_capacity = (self.max_position_deposit != 0)
+            .then(|| self.max_position_deposit.saturating_sub(position.deposited));
+
+        match (fund_capacity, position_capacity) {
+            (Some(fund), Some(position)) => Some(fund.min(position)),
+            (fund, position) => fund.or(position),
+        }
+    }
+
+    /// Returns (oracle, feed id, decimals) of `mint` if the fund holds it.
+    pub fn asset(&self, mint: &Pubkey) -> Option<(Pubkey, [u8; 32], u8)> {
+        if *mint == self.main_mint {
+            return Some((self.main_oracle, self.main_feed_id, self.main_decimals));
+        }
+
+        self.other_mints.binary_search(mint).ok().map(|pos| {
+            (
+                self.other_oracles[pos],
+                self.other_feed_ids[pos],
+                self.other_decimals[pos],
+            )
+        })
+    }
+
+    /// Returns (mint, oracle, feed id, decimals) for each mint in `other_mints`, in order.
+    pub fn other_assets(&self) -> impl Iterator<Item = (&Pubkey, &Pubkey, &[u8; 32], u8)> {
+        self.other_mints
+            .iter()
+            .zip(self.other_oracles.iter())
+            .zip(self.other_feed_ids.iter())
+            .zip(self.other_decimals.iter())
+            .map(|(((mint, oracle), feed_id), decimals)| (mint, oracle, feed_id, *decimals))
+    }
 }
 
 account!(YokoProgramAccount, Fund);
@@ -37,6 +334,12 @@ pub struct ArraySet {
     len: u64,
 }
 
+impl Default for ArraySet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ArraySet {
     pub const fn new() -> Self {
         Self {
@@ -101,3 +404,140 @@ impl ArraySet {
         self.data[..self.len()].binary_search(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{management_fund, performance_fund};
+
+    #[test]
+    fn hurdle_is_above_the_high_water_mark() {
+        let mut fund = performance_fund(1_000, 2_000, 500);
+        assert_eq!(fund.hurdle_nav_per_share(), Ok(1_050_000_000));
+
+        fund.hurdle_bps = 0;
+        assert_eq!(fund.hurdle_nav_per_share(), Ok(NAV_PER_SHARE_SCALE));
+
+        fund.high_water_mark = u64::MAX;
+        fund.hurdle_bps = 1;
+        assert_eq!(
+            fund.hurdle_nav_per_share(),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn no_performance_fee_up_to_the_hurdle() {
+        let fund = performance_fund(1_000, 2_000, 500);
+
+        assert_eq!(fund.performance_fee(900), Ok(0));
+        assert_eq!(fund.performance_fee(1_000), Ok(0));
+        assert_eq!(fund.performance_fee(1_050), Ok(0));
+    }
+
+    #[test]
+    fn performance_fee_is_charged_on_gains_above_the_hurdle() {
+        let fund = performance_fund(1_000, 2_000, 500);
+
+        // 150 of gains above the 1_050 hurdle, 20% of which is the fee.
+        assert_eq!(fund.performance_fee(1_200), Ok(30));
+        // Rounds down.
+        assert_eq!(fund.performance_fee(1_054), Ok(0));
+        assert_eq!(fund.performance_fee(1_055), Ok(1));
+    }
+
+    #[test]
+    fn performance_fee_uses_the_high_water_mark() {
+        let mut fund = performance_fund(1_000, 2_000, 0);
+        fund.high_water_mark = 2 * NAV_PER_SHARE_SCALE;
+
+        // Recovering up to the previous high is not a gain.
+        assert_eq!(fund.performance_fee(2_000), Ok(0));
+        assert_eq!(fund.performance_fee(2_500), Ok(100));
+    }
+
+    #[test]
+    fn no_performance_fee_without_shares() {
+        let fund = performance_fund(0, 2_000, 0);
+
+        assert_eq!(fund.performance_fee(1_000_000), Ok(0));
+    }
+
+    #[test]
+    fn management_fee_accrues_over_a_year() {
+        let fund = management_fund(1_000, 200);
+
+        assert_eq!(
+            fund.pending_management_fee(1_000_000, 1_000 + SECONDS_PER_YEAR),
+            Ok(20_000)
+        );
+        assert_eq!(
+            fund.pending_management_fee(1_000_000, 1_000 + SECONDS_PER_YEAR / 2),
+            Ok(10_000)
+        );
+    }
+
+    #[test]
+    fn management_fee_rounds_down() {
+        let fund = management_fund(1_000, 200);
+
+        assert_eq!(fund.pending_management_fee(1_000_000, 1_001), Ok(0));
+        // 1_000_000 * 2% / 365 days is 54.79 per day.
+        assert_eq!(
+            fund.pending_management_fee(1_000_000, 1_000 + 24 * 60 * 60),
+            Ok(54)
+        );
+    }
+
+    #[test]
+    fn no_management_fee_without_elapsed_time_or_shares() {
+        let fund = management_fund(1_000, 200);
+        assert_eq!(fund.pending_management_fee(1_000_000, 1_000), Ok(0));
+        assert_eq!(fund.pending_management_fee(1_000_000, 0), Ok(0));
+
+        let fund = management_fund(0, 200);
+        assert_eq!(
+            fund.pending_management_fee(1_000_000, 1_000 + SECONDS_PER_YEAR),
+            Ok(0)
+        );
+
+        let fund = management_fund(1_000, 0);
+        assert_eq!(
+            fund.pending_management_fee(1_000_000, 1_000 + SECONDS_PER_YEAR),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn management_fee_is_accrued_until_what_was_taken_pays_for() {
+        let fund = management_fund(1_000, 200);
+        let now = 1_000 + SECONDS_PER_YEAR;
+
+        assert_eq!(
+            fund.management_fee_accrued_until(now, 20_000, 20_000),
+            Ok(now)
+        );
+        assert_eq!(
+            fund.management_fee_accrued_until(now, 20_000, 5_000),
+            Ok(1_000 + SECONDS_PER_YEAR / 4)
+        );
+        assert_eq!(fund.management_fee_accrued_until(now, 20_000, 0), Ok(1_000));
+        // Rounds down.
+        assert_eq!(fund.management_fee_accrued_until(1_003, 2, 1), Ok(1_001));
+
+        // The rest of the fee is owed on the time not paid for.
+        let mut fund = fund;
+        fund.last_management_fee_accrual = 1_000 + SECONDS_PER_YEAR / 4;
+        assert_eq!(fund.pending_management_fee(1_000_000, now), Ok(15_000));
+    }
+
+    #[test]
+    fn max_management_fee_does_not_overflow() {
+        let fund = management_fund(1_000, MAX_MANAGEMENT_FEE_BPS);
+
+        assert_eq!(
+            fund.pending_management_fee(u64::MAX, 1_000 + SECONDS_PER_YEAR),
+            Ok(u64::MAX / 10)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use steel::*;
+
+use super::{ArraySet, YokoProgramAccount};
+
+/// Seeds = [MANDATE, fund]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Mandate {
+    pub fund: Pubkey,
+
+    /// Mints the fund may hold besides its main mint. Fixed once the mandate is created.
+    pub mints: ArraySet,
+}
+
+account!(YokoProgramAccount, Mandate);
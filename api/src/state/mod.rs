@@ -1,10 +1,16 @@
+mod allowlist_entry;
 mod fund;
+mod mandate;
 mod payout;
 mod position;
+mod protocol_config;
 
+pub use allowlist_entry::*;
 pub use fund::*;
+pub use mandate::*;
 pub use payout::*;
 pub use position::*;
+pub use protocol_config::*;
 use steel::*;
 
 use crate::consts::*;
@@ -15,10 +21,28 @@ pub enum YokoProgramAccount {
     Fund = 0,
     Position = 1,
     Payout = 2,
+    ProtocolConfig = 3,
+    Mandate = 4,
+    AllowlistEntry = 5,
 }
 
-pub fn fund_pda(authority: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[FUND, authority.as_ref()], &crate::id())
+pub fn fund_pda(creator: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FUND, creator.as_ref(), &id.to_le_bytes()], &crate::id())
+}
+
+pub fn protocol_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROTOCOL_CONFIG], &crate::id())
+}
+
+pub fn mandate_pda(fund: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MANDATE, fund.as_ref()], &crate::id())
+}
+
+pub fn allowlist_entry_pda(fund: &Pubkey, depositor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ALLOWLIST, fund.as_ref(), depositor.as_ref()],
+        &crate::id(),
+    )
 }
 
 pub fn position_pda(fund: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
@@ -39,3 +63,7 @@ pub fn payout_pda(fund: &Pubkey, counter: u64) -> (Pubkey, u8) {
 pub fn payout_token_account_pda(payout: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[PAYOUT, payout.as_ref()], &crate::id())
 }
+
+pub fn wsol_account_pda(position: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WSOL, position.as_ref()], &crate::id())
+}
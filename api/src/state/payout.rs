@@ -1,12 +1,108 @@
 use steel::*;
 
+use crate::math::pro_rata;
+
 use super::YokoProgramAccount;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
 pub struct Payout {
-    pub total_deposited: u64, // total deposited in the fund when the payout was created
-    pub amount_transferred_on_creation: u64, // amount transferred to the payout main token account on creation
+    pub total_shares: u64, // total shares of the fund when the payout was created
+    pub amount_transferred_on_creation: u64, // amount transferred to the payout token account on creation
+    pub mint: Pubkey, // mint the payout is paid in, the fund main mint unless paid in kind
+    pub claimed_amount: u64, // amount claimed so far
+    pub claimed_shares: u64, // shares of the positions that claimed so far
+    pub rent_payer: Pubkey, // receives the rent of the payout accounts when they are closed
+    pub claim_deadline: i64, // after it the fund authority can reclaim what is left, 0 for no deadline
+}
+
+impl Payout {
+    /// Records the claim of a position with `shares` and returns the amount it receives.
+    ///
+    /// Positions cannot change their shares with unclaimed payouts, so the claimed shares add up to
+    /// the payout's total shares. The last claimant takes the remainder and the claims add up to
+    /// exactly the amount paid out.
+    pub fn claim(&mut self, shares: u64) -> Result<u64, ProgramError> {
+        self.claimed_shares = self
+            .claimed_shares
+            .checked_add(shares)
+            .filter(|claimed_shares| *claimed_shares <= self.total_shares)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        let amount = if self.claimed_shares == self.total_shares {
+            self.amount_transferred_on_creation - self.claimed_amount
+        } else {
+            pro_rata(
+                self.amount_transferred_on_creation,
+                shares,
+                self.total_shares,
+            )?
+        };
+        self.claimed_amount += amount;
+
+        Ok(amount)
+    }
 }
 
 account!(YokoProgramAccount, Payout);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::payout;
+
+    #[test]
+    fn claims_pro_rata() {
+        let mut payout = payout(1_000, 400);
+
+        assert_eq!(payout.claim(100), Ok(250));
+        assert_eq!(payout.claim(300), Ok(750));
+        assert_eq!(payout.claimed_amount, 1_000);
+        assert_eq!(payout.claimed_shares, 400);
+    }
+
+    #[test]
+    fn last_claimant_takes_the_remainder() {
+        let mut payout = payout(100, 3);
+
+        // Each third rounds down to 33, the last claimant gets the leftover unit.
+        assert_eq!(payout.claim(1), Ok(33));
+        assert_eq!(payout.claim(1), Ok(33));
+        assert_eq!(payout.claim(1), Ok(34));
+        assert_eq!(payout.claimed_amount, 100);
+    }
+
+    #[test]
+    fn claims_add_up_to_the_amount_paid_out() {
+        let shares = [7, 1, 13, 999, 2, 41];
+        let mut payout = payout(1_000_003, shares.iter().sum());
+
+        let claimed: u64 = shares
+            .iter()
+            .map(|shares| payout.claim(*shares).unwrap())
+            .sum();
+
+        assert_eq!(claimed, 1_000_003);
+        assert_eq!(payout.claimed_amount, 1_000_003);
+    }
+
+    #[test]
+    fn rejects_claims_above_the_total_shares() {
+        let mut payout = payout(1_000, 400);
+
+        assert_eq!(payout.claim(300), Ok(750));
+        assert_eq!(payout.claim(101), Err(ProgramError::InvalidAccountData));
+        assert_eq!(
+            payout.claim(u64::MAX),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn pro_rata_rounds_down() {
+        assert_eq!(pro_rata(100, 1, 3), Ok(33));
+        assert_eq!(pro_rata(100, 2, 3), Ok(66));
+        assert_eq!(pro_rata(u64::MAX, u64::MAX, u64::MAX), Ok(u64::MAX));
+        assert_eq!(pro_rata(100, 1, 0), Err(ProgramError::ArithmeticOverflow));
+    }
+}
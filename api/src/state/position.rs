@@ -1,6 +1,6 @@
 use steel::*;
 
-use super::YokoProgramAccount;
+use super::{Fund, YokoProgramAccount};
 
 /// Seeds = [POSITION, fund, authority]
 #[repr(C)]
@@ -10,9 +10,43 @@ pub struct Position {
 
     pub fund: Pubkey,
 
-    pub deposited: u64,
+    pub shares: u64,
 
     pub payouts_counter: u64,
+
+    /// Main mint base units deposited and not yet withdrawn. Withdrawals reduce it pro rata to the
+    /// shares burned.
+    pub deposited: u64,
+
+    /// Unix timestamp of the last deposit.
+    pub last_deposit_ts: i64,
+
+    /// Unix timestamp of the pending redemption request, zero if there is none.
+    pub redemption_requested_at: i64,
+}
+
+impl Position {
+    /// Unix timestamp from which the fund lockup no longer blocks withdrawing.
+    pub fn unlocks_at(&self, fund: &Fund) -> i64 {
+        self.last_deposit_ts.saturating_add(fund.lockup_period)
+    }
+
+    /// Unix timestamp from which the position can be withdrawn, or `None` if the fund requires a
+    /// notice and no redemption was requested.
+    pub fn withdrawable_at(&self, fund: &Fund) -> Option<i64> {
+        if fund.notice_period == 0 {
+            return Some(self.unlocks_at(fund));
+        }
+
+        if self.redemption_requested_at == 0 {
+            return None;
+        }
+
+        let notice_ends_at = self
+            .redemption_requested_at
+            .saturating_add(fund.notice_period);
+        Some(self.unlocks_at(fund).max(notice_ends_at))
+    }
 }
 
 account!(YokoProgramAccount, Position);
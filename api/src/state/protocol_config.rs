@@ -0,0 +1,65 @@
+use steel::*;
+
+use super::YokoProgramAccount;
+use crate::consts::MAX_SWAP_PROGRAMS;
+
+/// Seeds = [PROTOCOL_CONFIG]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct ProtocolConfig {
+    /// Can update the protocol config, including handing it to a new admin.
+    pub admin: Pubkey,
+
+    /// Owner of the token accounts that receive protocol fees.
+    pub fee_recipient: Pubkey,
+
+    /// Share of every payout taken by the protocol, in basis points.
+    pub protocol_fee_bps: u64,
+
+    /// Non-zero when the protocol is paused.
+    pub paused: u8,
+
+    pub _padding: [u8; 7],
+
+    /// Programs funds are allowed to swap through. Unused slots hold the default pubkey.
+    pub swap_programs: [Pubkey; MAX_SWAP_PROGRAMS],
+}
+
+impl ProtocolConfig {
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
+    }
+
+    pub fn is_swap_program_allowed(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.swap_programs.contains(program)
+    }
+
+    /// Adds `program` to the allowlist. Returns false when the list is full.
+    pub fn allow_swap_program(&mut self, program: Pubkey) -> bool {
+        if self.is_swap_program_allowed(&program) {
+            return true;
+        }
+
+        match self
+            .swap_programs
+            .iter_mut()
+            .find(|slot| **slot == Pubkey::default())
+        {
+            Some(slot) => {
+                *slot = program;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn disallow_swap_program(&mut self, program: &Pubkey) {
+        for slot in self.swap_programs.iter_mut() {
+            if slot == program {
+                *slot = Pubkey::default();
+            }
+        }
+    }
+}
+
+account!(YokoProgramAccount, ProtocolConfig);
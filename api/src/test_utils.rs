@@ -0,0 +1,68 @@
+//! Fixtures shared by the unit tests of this crate and the processor tests of the program.
+
+use steel::*;
+
+use crate::{consts::*, oracle::PythPriceUpdate, state::*};
+
+/// A fund charging `authority_fee_bps` of the gains above `hurdle_bps` over a high-water mark of
+/// one main mint base unit per share.
+pub fn performance_fund(total_shares: u64, authority_fee_bps: u64, hurdle_bps: u64) -> Fund {
+    Fund {
+        total_shares,
+        authority_fee_bps,
+        hurdle_bps,
+        high_water_mark: NAV_PER_SHARE_SCALE,
+        fee_mode: FeeMode::Performance as u8,
+        ..Fund::zeroed()
+    }
+}
+
+/// A fund charging an annual management fee of `management_fee_bps`, last accrued at 1_000.
+pub fn management_fund(total_shares: u64, management_fee_bps: u64) -> Fund {
+    Fund {
+        total_shares,
+        management_fee_bps,
+        last_management_fee_accrual: 1_000,
+        ..Fund::zeroed()
+    }
+}
+
+/// A payout of `amount` to `total_shares` shares that nobody claimed yet.
+pub fn payout(amount: u64, total_shares: u64) -> Payout {
+    Payout {
+        total_shares,
+        amount_transferred_on_creation: amount,
+        ..Payout::zeroed()
+    }
+}
+
+/// Data of a Pyth `PriceUpdateV2` account with a `price * 10^-6` of `feed_id`.
+/// `verification_level` is `[1]` for a fully verified update.
+pub fn price_update(
+    verification_level: &[u8],
+    feed_id: &[u8; 32],
+    price: i64,
+    publish_time: i64,
+) -> Vec<u8> {
+    let mut data = PythPriceUpdate::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&[1; 32]);
+    data.extend_from_slice(verification_level);
+    data.extend_from_slice(feed_id);
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&10u64.to_le_bytes());
+    data.extend_from_slice(&(-6i32).to_le_bytes());
+    data.extend_from_slice(&publish_time.to_le_bytes());
+    data.extend_from_slice(&(publish_time - 1).to_le_bytes());
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&10u64.to_le_bytes());
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data
+}
+
+/// Data of a program account holding `state`.
+pub fn account_data<T: Discriminator + Pod>(state: &T) -> Vec<u8> {
+    let mut data = vec![0; 8];
+    data[0] = T::discriminator();
+    data.extend_from_slice(bytemuck::bytes_of(state));
+    data
+}
@@ -0,0 +1,62 @@
+use spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::{Account, Mint},
+};
+use steel::*;
+
+/// Whether `program_id` is classic SPL Token or Token-2022.
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::ID || *program_id == spl_token_2022::ID
+}
+
+/// Unpacks a token account of either token program, ignoring any extensions.
+pub fn unpack_token_account(data: &[u8]) -> Result<Account, ProgramError> {
+    Ok(StateWithExtensions::<Account>::unpack(data)?.base)
+}
+
+/// Unpacks a mint of either token program, ignoring any extensions.
+pub fn unpack_mint(data: &[u8]) -> Result<Mint, ProgramError> {
+    Ok(StateWithExtensions::<Mint>::unpack(data)?.base)
+}
+
+/// Size of a token account for the mint, including the extensions the mint requires on its
+/// accounts.
+pub fn token_account_len(mint_data: &[u8]) -> Result<usize, ProgramError> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    let account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+
+    ExtensionType::try_calculate_account_len::<Account>(&account_extensions)
+}
+
+/// Fee the mint withholds when `amount` is transferred in `epoch`. Zero for mints without the
+/// transfer fee extension.
+pub fn transfer_fee(mint_data: &[u8], epoch: u64, amount: u64) -> Result<u64, ProgramError> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(ProgramError::ArithmeticOverflow),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Transfer fees withheld in the token account. Zero for accounts without the transfer fee
+/// extension.
+pub fn withheld_amount(data: &[u8]) -> Result<u64, ProgramError> {
+    let account = StateWithExtensions::<Account>::unpack(data)?;
+
+    Ok(account
+        .get_extension::<TransferFeeAmount>()
+        .map(|transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount))
+        .unwrap_or(0))
+}
+
+/// Whether the token account can be closed: it is empty and holds no withheld transfer fees.
+pub fn is_token_account_closable(data: &[u8]) -> Result<bool, ProgramError> {
+    Ok(unpack_token_account(data)?.amount == 0 && withheld_amount(data)? == 0)
+}
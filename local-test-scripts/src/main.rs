@@ -1,10 +1,8 @@
-use base64;
 use serde::Serialize;
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
-use solana_client::rpc_filter::{Memcmp, RpcFilterType};
-use solana_sdk::account::Account;
+use solana_client::rpc_filter::RpcFilterType;
 use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::{
     message::v0::Message as MessageV0, program_pack::Pack, pubkey::Pubkey, signature::Signer,
@@ -15,15 +13,16 @@ use spl_associated_token_account::instruction::create_associated_token_account_i
 use spl_token::state::Account as SplTokenAccount;
 use std::str::FromStr;
 use steel::*;
-use yoko_program_api::state::ArraySet;
 use yoko_program_api::{
+    consts::JUPITER_PROGRAM_ID,
     sdk::{
         claim_payout, create_fund, create_fund_token_account, create_payout, create_position,
-        deposit, swap,
+        deposit, fund_valuation_accounts, initialize_protocol, swap, swap_valuation_accounts,
+        withdraw,
     },
     state::{
-        fund_pda, fund_token_account_pda, payout_pda, payout_token_account_pda, position_pda, Fund,
-        Payout, Position,
+        fund_pda, fund_token_account_pda, payout_pda, payout_token_account_pda, position_pda,
+        protocol_config_pda, FeeMode, Fund, Payout, Position, ProtocolConfig,
     },
 };
 
@@ -32,6 +31,8 @@ struct YokoConfig {
     fund_manager: solana_sdk::signer::keypair::Keypair,
     depositor: solana_sdk::signer::keypair::Keypair,
     mint: Pubkey,
+    mint_oracle: Pubkey,
+    fund_id: u64,
 }
 
 impl YokoConfig {
@@ -44,12 +45,16 @@ impl YokoConfig {
         ).expect("Failed to load depositor keypair");
         let mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
             .expect("Failed to parse mint address");
+        let mint_oracle = Pubkey::from_str("Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX")
+            .expect("Failed to parse mint oracle address");
 
         Self {
             client,
             fund_manager,
             depositor,
             mint,
+            mint_oracle,
+            fund_id: 0,
         }
     }
 
@@ -62,7 +67,7 @@ impl YokoConfig {
     }
 
     fn get_fund(&self) -> Result<(Pubkey, Fund), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let fund_data = self.client.get_account_data(&fund)?;
         Ok((fund, *Fund::try_from_bytes(&fund_data)?))
     }
@@ -81,7 +86,7 @@ impl YokoConfig {
         &self,
         mint: Pubkey,
     ) -> Result<(Pubkey, SplTokenAccount), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let token_account = fund_token_account_pda(&fund, &mint).0;
         let token_account_data = self.client.get_account_data(&token_account)?;
         let token_account_data = SplTokenAccount::unpack(&token_account_data)?;
@@ -89,19 +94,24 @@ impl YokoConfig {
     }
 
     fn get_position(&self) -> Result<(Pubkey, Position), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let position = position_pda(&fund, &self.depositor.pubkey()).0;
         let position_data = self.client.get_account_data(&position)?;
         Ok((position, *Position::try_from_bytes(&position_data)?))
     }
 
     fn get_payout(&self, counter: u64) -> Result<Payout, Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let payout = payout_pda(&fund, counter).0;
         let payout_data = self.client.get_account_data(&payout)?;
         Ok(*Payout::try_from_bytes(&payout_data)?)
     }
 
+    fn get_protocol_config(&self) -> Result<ProtocolConfig, Box<dyn std::error::Error>> {
+        let protocol_config_data = self.client.get_account_data(&protocol_config_pda().0)?;
+        Ok(*ProtocolConfig::try_from_bytes(&protocol_config_data)?)
+    }
+
     fn send_transaction(
         &self,
         ixns: Vec<solana_sdk::instruction::Instruction>,
@@ -134,30 +144,50 @@ impl YokoConfig {
         Ok(())
     }
 
+    fn initialize_protocol(&self, protocol_fee_bps: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let instruction = initialize_protocol(
+            self.fund_manager.pubkey(),
+            self.fund_manager.pubkey(),
+            protocol_fee_bps,
+        );
+        self.send_transaction(vec![instruction], &self.fund_manager)
+    }
+
     fn create_fund(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let main_token_account = fund_token_account_pda(&fund, &self.mint).0;
         let instruction = create_fund(
             fund,
             self.fund_manager.pubkey(),
             self.mint,
             main_token_account,
-            10,
+            self.mint_oracle,
+            spl_token::ID,
+            self.fund_id,
+            1_000,
+            FeeMode::Payout,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
         );
         self.send_transaction(vec![instruction], &self.fund_manager)
     }
 
     fn create_position(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let position = position_pda(&fund, &self.depositor.pubkey()).0;
-        let instruction = create_position(position, fund, self.depositor.pubkey());
+        let instruction = create_position(position, fund, self.depositor.pubkey(), None, vec![]);
         self.send_transaction(vec![instruction], &self.depositor)
     }
 
     fn deposit(&self, amount: u64) -> Result<(), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let position = position_pda(&fund, &self.depositor.pubkey()).0;
         let main_token_account = fund_token_account_pda(&fund, &self.mint).0;
+        let fund_data = self.get_fund()?.1;
 
         let instruction = deposit(
             position,
@@ -165,19 +195,23 @@ impl YokoConfig {
             main_token_account,
             self.depositor.pubkey(),
             self.get_depositor_token_account(),
+            self.mint,
+            spl_token::ID,
+            self.get_fund_manager_token_account(),
+            fund_valuation_accounts(&fund, &fund_data),
             amount,
         );
         self.send_transaction(vec![instruction], &self.depositor)
     }
 
     fn create_payout(&self, amount: u64) -> Result<(), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let fund_main_token_account = fund_token_account_pda(&fund, &self.mint).0;
         let fund_data = self.get_fund()?.1;
         let payout = payout_pda(&fund, fund_data.payouts_counter + 1).0;
         let payout_main_token_account = payout_token_account_pda(&payout).0;
         let protocol_fee_token_account = get_associated_token_address(
-            &Pubkey::from_str("H61JjSDPCwvAs1k2vaPAX6d917Pu4dPWykcexvXXzGph").unwrap(),
+            &self.get_protocol_config()?.fee_recipient,
             &fund_data.main_mint,
         );
 
@@ -197,14 +231,17 @@ impl YokoConfig {
                 payout_main_token_account,
                 self.mint,
                 protocol_fee_token_account,
+                spl_token::ID,
+                fund_valuation_accounts(&fund, &fund_data),
                 amount,
+                0,
             ),
         ];
         self.send_transaction(ixns, &self.fund_manager)
     }
 
     fn claim_payout(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
         let position = position_pda(&fund, &self.depositor.pubkey()).0;
         let position_data = self.get_position()?.1;
         let payout = payout_pda(&fund, position_data.payouts_counter + 1).0;
@@ -224,6 +261,37 @@ impl YokoConfig {
                 payout_main_token_account,
                 self.get_depositor_token_account(),
                 fund,
+                self.mint,
+                spl_token::ID,
+            ),
+        ];
+        self.send_transaction(ixns, &self.depositor)
+    }
+
+    fn withdraw(&self, shares: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
+        let position = position_pda(&fund, &self.depositor.pubkey()).0;
+        let main_token_account = fund_token_account_pda(&fund, &self.mint).0;
+
+        let ixns = vec![
+            create_associated_token_account_idempotent(
+                &self.depositor.pubkey(),
+                &self.depositor.pubkey(),
+                &self.mint,
+                &spl_token::ID,
+            ),
+            withdraw(
+                position,
+                self.depositor.pubkey(),
+                fund,
+                main_token_account,
+                self.get_depositor_token_account(),
+                self.mint,
+                spl_token::ID,
+                self.get_fund_manager_token_account(),
+                vec![],
+                vec![],
+                shares,
             ),
         ];
         self.send_transaction(ixns, &self.depositor)
@@ -234,8 +302,16 @@ impl YokoConfig {
         fund: Pubkey,
         fund_token_account: Pubkey,
         mint: Pubkey,
+        oracle: Pubkey,
     ) -> Instruction {
-        create_fund_token_account(fund, self.fund_manager.pubkey(), fund_token_account, mint)
+        create_fund_token_account(
+            fund,
+            self.fund_manager.pubkey(),
+            fund_token_account,
+            mint,
+            oracle,
+            spl_token::ID,
+        )
     }
 
     fn get_fund_source_token_account(
@@ -263,36 +339,63 @@ impl YokoConfig {
         ))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn get_swap_ixns(
         &self,
         jupiter_route_cpi_data: &[u8],
         jupiter_accounts_metas: Vec<AccountMeta>,
         from_mint: Pubkey,
         to_mint: Pubkey,
+        to_mint_oracle: Option<Pubkey>,
         in_amount: u64,
+        min_out_amount: u64,
     ) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
-        let fund = fund_pda(&self.fund_manager.pubkey()).0;
+        let fund = fund_pda(&self.fund_manager.pubkey(), self.fund_id).0;
 
         let (fund_destination_token_account, need_to_create_fund_token_account) =
             self.get_fund_destination_token_account(to_mint)?;
 
+        let mut fund_data = self.get_fund()?.1;
+        let source_oracle = fund_data
+            .asset(&from_mint)
+            .ok_or("fund does not hold from_mint")?
+            .0;
+
         let mut instructions = vec![];
-        if need_to_create_fund_token_account {
+        let destination_oracle = if need_to_create_fund_token_account {
+            let to_mint_oracle = to_mint_oracle
+                .ok_or("to_mint_oracle is required to create a fund token account")?;
             instructions.push(self.get_create_fund_token_account_ixn(
                 fund,
                 fund_destination_token_account,
                 to_mint,
+                to_mint_oracle,
             ));
-        }
+            fund_data.insert_other_mint(to_mint, to_mint_oracle, [0; 32], 0);
+            to_mint_oracle
+        } else {
+            fund_data
+                .asset(&to_mint)
+                .ok_or("fund does not hold to_mint")?
+                .0
+        };
 
         instructions.push(swap(
             self.fund_manager.pubkey(),
             fund,
             self.get_fund_source_token_account(from_mint)?,
             fund_destination_token_account,
-            Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap(),
+            source_oracle,
+            destination_oracle,
+            JUPITER_PROGRAM_ID,
+            from_mint,
+            to_mint,
+            spl_token::ID,
+            spl_token::ID,
+            swap_valuation_accounts(&fund, &fund_data),
             jupiter_accounts_metas,
             in_amount,
+            min_out_amount,
             jupiter_route_cpi_data,
         ));
 
@@ -332,10 +435,7 @@ async fn get_jup_quote(
 
     let url = format!(
         "{}/quote?outputMint={}&inputMint={}&amount={}&slippage=0.5&onlyDirectRoutes=true",
-        JUP_API_ENDPOINT,
-        to_mint.to_string(),
-        from_mint.to_string(),
-        amount
+        JUP_API_ENDPOINT, to_mint, from_mint, amount
     );
 
     let response = client
@@ -369,7 +469,7 @@ async fn get_jup_swap_ixn(
     };
 
     let response = client
-        .post(&format!("{}/swap-instructions", JUP_API_ENDPOINT))
+        .post(format!("{}/swap-instructions", JUP_API_ENDPOINT))
         .header("Accept", "application/json")
         .header("Content-Type", "application/json")
         .json(&data)
@@ -387,6 +487,11 @@ async fn do_swap(config: &YokoConfig) {
     let amount = 2_000_000;
 
     let quote = get_jup_quote(from_mint, to_mint, amount).await.unwrap();
+    let min_out_amount = quote["otherAmountThreshold"]
+        .as_str()
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
 
     let swap_ixn = get_jup_swap_ixn(config.fund_manager.pubkey().to_string().as_str(), quote)
         .await
@@ -429,7 +534,7 @@ async fn do_swap(config: &YokoConfig) {
 
                     solana_sdk::address_lookup_table_account::AddressLookupTableAccount {
                         key: *address,
-                        addresses: lookup_table.addresses.into_iter().copied().collect(),
+                        addresses: lookup_table.addresses.iter().copied().collect(),
                     }
                 })
                 .unwrap()
@@ -455,7 +560,9 @@ async fn do_swap(config: &YokoConfig) {
                 jupiter_accounts,
                 Pubkey::from_str(from_mint).unwrap(),
                 Pubkey::from_str(to_mint).unwrap(),
+                None,
                 amount,
+                min_out_amount,
             )
             .unwrap(),
     );
@@ -511,37 +618,63 @@ pub fn fetch_program_accounts(
     Ok(accounts.into_iter().map(|(pubkey, _)| pubkey).collect())
 }
 
+/// Runs the script named by the first argument, e.g. `cargo run -p local-test-scripts -- deposit
+/// 1000000`, and counts the program accounts when there is none.
 #[tokio::main]
 async fn main() {
     let config = YokoConfig::new();
 
-    let program_id = Pubkey::from_str("4NmD5nA9Rd8SCgW6kXyG1zzUGkfDg3TUiZTmPEMM3ZLU").unwrap();
-    let accounts = fetch_program_accounts(&program_id, &config.client).unwrap();
-    println!("accounts len: {:?}", accounts.len());
-
-    // let (fund, fund_data) = config.get_fund().unwrap();
-    // println!("fund: {:?}", fund);
-    // println!("fund_data: {:?}", fund_data);
-
-    // for mint in fund_data.other_mints.iter() {
-    //     println!("mint: {:?}", mint);
-    //     let (token_account, token_account_data) =
-    //         config.get_fund_token_account_data(*mint).unwrap();
-    //     println!("token_account: {:?}", token_account);
-    //     println!("token_account_data: {:?}", token_account_data);
-    // }
-
-    // let fund_main_token_account_data = config.get_fund_main_token_account_data().unwrap();
-    // println!(
-    //     "fund_main_token_account_data: {:?}",
-    //     fund_main_token_account_data
-    // );
-
-    // let (position, position_data) = config.get_position().unwrap();
-    // println!("position: {:?}", position);
-    // println!("position_data: {:?}", position_data);
-
-    // do_swap(&config).await;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let number_arg = || -> u64 {
+        args.get(1)
+            .expect("Missing number argument")
+            .parse()
+            .expect("Failed to parse number argument")
+    };
 
-    // config.claim_payout().unwrap();
+    match args.first().map(String::as_str).unwrap_or("accounts") {
+        "accounts" => {
+            let program_id =
+                Pubkey::from_str("4NmD5nA9Rd8SCgW6kXyG1zzUGkfDg3TUiZTmPEMM3ZLU").unwrap();
+            let accounts = fetch_program_accounts(&program_id, &config.client).unwrap();
+            println!("accounts len: {:?}", accounts.len());
+        }
+        "fund" => {
+            let (fund, fund_data) = config.get_fund().unwrap();
+            println!("fund: {:?}", fund);
+            println!("fund_data: {:?}", fund_data);
+
+            for mint in fund_data.other_mints.iter() {
+                println!("mint: {:?}", mint);
+                let (token_account, token_account_data) =
+                    config.get_fund_token_account_data(*mint).unwrap();
+                println!("token_account: {:?}", token_account);
+                println!("token_account_data: {:?}", token_account_data);
+            }
+
+            let fund_main_token_account_data = config.get_fund_main_token_account_data().unwrap();
+            println!(
+                "fund_main_token_account_data: {:?}",
+                fund_main_token_account_data
+            );
+        }
+        "position" => {
+            let (position, position_data) = config.get_position().unwrap();
+            println!("position: {:?}", position);
+            println!("position_data: {:?}", position_data);
+        }
+        "payout" => {
+            let payout_data = config.get_payout(number_arg()).unwrap();
+            println!("payout_data: {:?}", payout_data);
+        }
+        "initialize-protocol" => config.initialize_protocol(number_arg()).unwrap(),
+        "create-fund" => config.create_fund().unwrap(),
+        "create-position" => config.create_position().unwrap(),
+        "deposit" => config.deposit(number_arg()).unwrap(),
+        "create-payout" => config.create_payout(number_arg()).unwrap(),
+        "claim-payout" => config.claim_payout().unwrap(),
+        "withdraw" => config.withdraw(number_arg()).unwrap(),
+        "swap" => do_swap(&config).await,
+        script => panic!("Unknown script: {}", script),
+    }
 }
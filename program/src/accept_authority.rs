@@ -0,0 +1,29 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_accept_authority(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [fund_info, new_authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    new_authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.pending_authority != Pubkey::default())?
+        .assert_mut(|fund| fund.pending_authority == *new_authority_info.key)?;
+
+    let previous_authority = fund.authority;
+    fund.authority = *new_authority_info.key;
+    fund.pending_authority = Pubkey::default();
+
+    AuthorityAccepted {
+        fund: *fund_info.key,
+        previous_authority,
+        authority: fund.authority,
+    }
+    .log();
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::token::token_account;
+
+/// Fails unless `depositor_info` may open a position under the fund's `AccessMode`.
+///
+/// `access_accounts` holds the `AllowlistEntry` for `AccessMode::Allowlist` or the depositor's
+/// gate mint token account for `AccessMode::TokenGate`. `merkle_proof` holds the proof nodes for
+/// `AccessMode::Merkle`, 32 bytes each.
+pub fn check_access(
+    fund_info: &AccountInfo<'_>,
+    fund: &Fund,
+    depositor_info: &AccountInfo<'_>,
+    access_accounts: &[AccountInfo<'_>],
+    merkle_proof: &[u8],
+) -> ProgramResult {
+    match fund.access_mode() {
+        AccessMode::Open => Ok(()),
+        AccessMode::Allowlist => {
+            let [allowlist_entry_info, ..] = access_accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            allowlist_entry_info.has_seeds(
+                &[
+                    ALLOWLIST,
+                    fund_info.key.as_ref(),
+                    depositor_info.key.as_ref(),
+                ],
+                &yoko_program_api::ID,
+            )?;
+
+            if allowlist_entry_info.data_is_empty() {
+                return Err(YokoProgramError::DepositorNotAllowed.into());
+            }
+
+            allowlist_entry_info.as_account::<AllowlistEntry>(&yoko_program_api::ID)?;
+
+            Ok(())
+        }
+        AccessMode::Merkle => {
+            let nodes = merkle_proof.chunks_exact(32);
+            if !nodes.remainder().is_empty() {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            let proof: Vec<[u8; 32]> = nodes.map(|node| node.try_into().unwrap()).collect();
+
+            if !verify_merkle_proof(&fund.merkle_root, merkle_leaf(depositor_info.key), &proof) {
+                return Err(YokoProgramError::DepositorNotAllowed.into());
+            }
+
+            Ok(())
+        }
+        AccessMode::TokenGate => {
+            let [token_account_info, ..] = access_accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            let token_account = token_account(token_account_info)?;
+            if token_account.owner != *depositor_info.key
+                || token_account.mint != fund.gate_mint
+                || token_account.amount == 0
+            {
+                return Err(YokoProgramError::DepositorNotAllowed.into());
+            }
+
+            Ok(())
+        }
+    }
+}
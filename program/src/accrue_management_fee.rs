@@ -0,0 +1,106 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    nav::fund_value,
+    token::{token_account, token_balance, transfer_checked_signed},
+};
+
+pub fn process_accrue_management_fee(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [fund_info, fund_main_token_account_info, fund_authority_token_account_info, main_mint_info, token_program, valuation_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // No pause check: the fee accrues through pauses, which only hold off deposits, swaps and
+    // payouts, and withdrawals collect it anyway.
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?;
+
+    let value = fund_value(
+        fund_info,
+        fund,
+        fund_main_token_account_info,
+        valuation_accounts,
+    )?;
+
+    accrue_management_fee(
+        &ManagementFeeAccounts {
+            fund: fund_info,
+            fund_main_token_account: fund_main_token_account_info,
+            fund_authority_token_account: fund_authority_token_account_info,
+            main_mint: main_mint_info,
+            token_program,
+        },
+        fund,
+        value,
+    )?;
+
+    Ok(())
+}
+
+pub struct ManagementFeeAccounts<'a, 'info> {
+    pub fund: &'a AccountInfo<'info>,
+    pub fund_main_token_account: &'a AccountInfo<'info>,
+    /// Main mint token account of the fund authority. Only checked when a fee is taken.
+    pub fund_authority_token_account: &'a AccountInfo<'info>,
+    pub main_mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+/// Pays the fund authority the management fee accrued on the fund `value` since the last accrual
+/// and returns the amount taken from the fund main token account.
+pub fn accrue_management_fee(
+    accounts: &ManagementFeeAccounts<'_, '_>,
+    fund: &mut Fund,
+    value: u64,
+) -> Result<u64, ProgramError> {
+    let now = Clock::get()?.unix_timestamp;
+    let fee = fund.pending_management_fee(value, now)?;
+
+    // Leave the accrual timestamp alone while the fee rounds to zero, so that frequent calls
+    // cannot skip it.
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    accounts.main_mint.has_address(&fund.main_mint)?;
+
+    let fund_authority_token_account_data = token_account(accounts.fund_authority_token_account)?;
+
+    if fund_authority_token_account_data.owner != fund.authority
+        || fund_authority_token_account_data.mint != fund.main_mint
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Only the time the taken amount pays for counts as accrued, so whatever the main balance
+    // cannot cover is collected later.
+    let amount = fee.min(token_balance(accounts.fund_main_token_account)?);
+    fund.last_management_fee_accrual = fund.management_fee_accrued_until(now, fee, amount)?;
+
+    if amount > 0 {
+        transfer_checked_signed(
+            accounts.fund,
+            accounts.fund_main_token_account,
+            accounts.fund_authority_token_account,
+            accounts.main_mint,
+            accounts.token_program,
+            amount,
+            &[FUND, fund.creator.as_ref(), &fund.id.to_le_bytes()],
+        )?;
+    }
+
+    ManagementFeeAccrued {
+        fund: *accounts.fund.key,
+        fund_value: value,
+        fee,
+        amount,
+        accrued_at: fund.last_management_fee_accrual,
+    }
+    .log();
+
+    Ok(amount)
+}
@@ -0,0 +1,51 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_add_to_allowlist(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [fund_info, authority_info, allowlist_entry_info, depositor_info, system_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    fund_info
+        .as_account::<Fund>(&yoko_program_api::ID)?
+        .assert(|fund| fund.authority == *authority_info.key)?;
+
+    allowlist_entry_info.is_writable()?.has_seeds(
+        &[
+            ALLOWLIST,
+            fund_info.key.as_ref(),
+            depositor_info.key.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
+
+    create_account::<AllowlistEntry>(
+        allowlist_entry_info,
+        system_program,
+        authority_info,
+        &yoko_program_api::ID,
+        &[
+            ALLOWLIST,
+            fund_info.key.as_ref(),
+            depositor_info.key.as_ref(),
+        ],
+    )?;
+
+    let allowlist_entry =
+        allowlist_entry_info.as_account_mut::<AllowlistEntry>(&yoko_program_api::ID)?;
+    allowlist_entry.fund = *fund_info.key;
+    allowlist_entry.depositor = *depositor_info.key;
+
+    AllowlistUpdated {
+        fund: *fund_info.key,
+        depositor: *depositor_info.key,
+        allowed: 1,
+    }
+    .log();
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
-use solana_program::{program::invoke_signed, program_pack::Pack};
-use spl_token::{instruction::close_account, state::Account as SplTokenAccount};
 use steel::*;
 use yoko_program_api::prelude::*;
 
+use crate::token::transfer_checked_signed;
+
 pub fn process_claim_payout(accounts: &[AccountInfo<'_>]) -> ProgramResult {
-    let [position_info, position_authority_info, payout_info, payout_main_token_account_info, depositor_main_token_account_info, fund_info, token_program] =
+    let [position_info, position_authority_info, payout_info, payout_token_account_info, depositor_token_account_info, fund_info, mint_info, token_program] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -17,8 +17,38 @@ pub fn process_claim_payout(accounts: &[AccountInfo<'_>]) -> ProgramResult {
     let position = position_info
         .as_account_mut::<Position>(&yoko_program_api::ID)?
         .assert_mut(|position| position.authority == *position_authority_info.key)?
-        .assert_mut(|position| position.fund == *fund_info.key)?
-        .assert_mut(|position| position.payouts_counter < fund.payouts_counter)?;
+        .assert_mut(|position| position.fund == *fund_info.key)?;
+
+    claim_next_payout(
+        position_info,
+        position,
+        fund,
+        fund_info,
+        payout_info,
+        payout_token_account_info,
+        depositor_token_account_info,
+        mint_info,
+        token_program,
+    )
+}
+
+/// Transfers the position's share of the payout after `position.payouts_counter` and advances
+/// the counter. Payouts closed in the meantime are skipped.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_next_payout<'info>(
+    position_info: &AccountInfo<'info>,
+    position: &mut Position,
+    fund: &Fund,
+    fund_info: &AccountInfo<'info>,
+    payout_info: &AccountInfo<'info>,
+    payout_token_account_info: &AccountInfo<'info>,
+    depositor_token_account_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> ProgramResult {
+    if position.payouts_counter >= fund.payouts_counter {
+        return Err(ProgramError::InvalidAccountData);
+    }
 
     let new_counter = position
         .payouts_counter
@@ -26,66 +56,41 @@ pub fn process_claim_payout(accounts: &[AccountInfo<'_>]) -> ProgramResult {
         .ok_or(ProgramError::ArithmeticOverflow)?;
     position.payouts_counter = new_counter;
 
-    let payout_info_pda = payout_pda(fund_info.key, new_counter);
-    if payout_info_pda.0 != *payout_info.key {
+    if payout_pda(fund_info.key, new_counter).0 != *payout_info.key {
         return Err(ProgramError::InvalidAccountData);
     }
-    let payout = payout_info.as_account::<Payout>(&yoko_program_api::ID)?;
 
-    let proportion = (position.deposited as u128)
-        .checked_mul(u128::pow(10, 9))
-        .ok_or(ProgramError::ArithmeticOverflow)?
-        .checked_div(payout.total_deposited as u128)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    // A payout closed before the position claimed it was reclaimed after its deadline, or held
+    // nothing for a position without shares, so there is nothing left to claim.
+    if payout_info.data_is_empty() {
+        return Ok(());
+    }
+    let payout = payout_info.as_account_mut::<Payout>(&yoko_program_api::ID)?;
+    mint_info.has_address(&payout.mint)?;
 
-    payout_main_token_account_info
+    payout_token_account_info
         .has_seeds(&[PAYOUT, payout_info.key.as_ref()], &yoko_program_api::ID)?;
 
-    let amount = (payout.amount_transferred_on_creation as u128)
-        .checked_mul(proportion)
-        .ok_or(ProgramError::ArithmeticOverflow)?
-        .checked_div(u128::pow(10, 9))
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    let amount = u64::try_from(amount).map_err(|_| ProgramError::ArithmeticOverflow)?;
+    let amount = payout.claim(position.shares)?;
 
-    transfer_signed(
+    transfer_checked_signed(
         payout_info,
-        payout_main_token_account_info,
-        depositor_main_token_account_info,
+        payout_token_account_info,
+        depositor_token_account_info,
+        mint_info,
         token_program,
         amount,
         &[PAYOUT, fund_info.key.as_ref(), &new_counter.to_le_bytes()],
     )?;
 
-    let payout_main_token_account_data =
-        SplTokenAccount::unpack(&payout_main_token_account_info.data.borrow())?;
-
-    if payout_main_token_account_data.amount <= 0 {
-        invoke_signed(
-            &close_account(
-                token_program.key,
-                payout_main_token_account_info.key,
-                position_authority_info.key,
-                payout_info.key,
-                &[payout_info.key],
-            )?,
-            &[
-                payout_main_token_account_info.clone(),
-                position_authority_info.clone(),
-                payout_info.clone(),
-                token_program.clone(),
-            ],
-            &[&[
-                PAYOUT,
-                fund_info.key.as_ref(),
-                &new_counter.to_le_bytes(),
-                &[payout_info_pda.1],
-            ]],
-        )?;
-
-        steel::close_account(payout_info, position_authority_info)?;
+    PayoutClaimed {
+        fund: *fund_info.key,
+        payout: *payout_info.key,
+        position: *position_info.key,
+        counter: new_counter,
+        amount,
     }
+    .log();
 
     Ok(())
 }
@@ -0,0 +1,74 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    claim_payout::claim_next_payout,
+    wsol::{close_wsol_account, create_wsol_account},
+};
+
+pub fn process_claim_payout_sol(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [position_info, position_authority_info, wsol_account_info, fund_info, native_mint_info, token_program, system_program, payout_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // (payout, payout token account) pairs for consecutive payouts.
+    if payout_accounts.is_empty() || payout_accounts.len() % 2 != 0 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    position_authority_info.is_signer()?.is_writable()?;
+
+    let fund = fund_info.as_account::<Fund>(&yoko_program_api::ID)?;
+    // Any payout paid in WSOL, whether the main mint or in kind, can be claimed unwrapped.
+    if *native_mint_info.key != spl_token::native_mint::ID {
+        return Err(YokoProgramError::NotNativeMint.into());
+    }
+
+    let position = position_info
+        .as_account_mut::<Position>(&yoko_program_api::ID)?
+        .assert_mut(|position| position.authority == *position_authority_info.key)?
+        .assert_mut(|position| position.fund == *fund_info.key)?;
+
+    // Payouts are claimed into the temporary WSOL account, which is then closed to unwrap them.
+    create_wsol_account(
+        position_authority_info,
+        wsol_account_info,
+        position_info,
+        native_mint_info,
+        token_program,
+        system_program,
+        0,
+    )?;
+
+    for payout_accounts in payout_accounts.chunks_exact(2) {
+        let [payout_info, payout_token_account_info] = payout_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        claim_next_payout(
+            position_info,
+            position,
+            fund,
+            fund_info,
+            payout_info,
+            payout_token_account_info,
+            wsol_account_info,
+            native_mint_info,
+            token_program,
+        )?;
+    }
+
+    close_wsol_account(
+        wsol_account_info,
+        position_info,
+        position_authority_info,
+        token_program,
+        &[
+            POSITION,
+            fund_info.key.as_ref(),
+            position_authority_info.key.as_ref(),
+        ],
+    )
+}
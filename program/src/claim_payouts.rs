@@ -0,0 +1,46 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::claim_payout::claim_next_payout;
+
+pub fn process_claim_payouts(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [position_info, position_authority_info, depositor_token_account_info, fund_info, mint_info, token_program, payout_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // (payout, payout token account) pairs for consecutive payouts, all paid in `mint`.
+    if payout_accounts.is_empty() || payout_accounts.len() % 2 != 0 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    position_authority_info.is_signer()?;
+
+    let fund = fund_info.as_account::<Fund>(&yoko_program_api::ID)?;
+
+    let position = position_info
+        .as_account_mut::<Position>(&yoko_program_api::ID)?
+        .assert_mut(|position| position.authority == *position_authority_info.key)?
+        .assert_mut(|position| position.fund == *fund_info.key)?;
+
+    for payout_accounts in payout_accounts.chunks_exact(2) {
+        let [payout_info, payout_token_account_info] = payout_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        claim_next_payout(
+            position_info,
+            position,
+            fund,
+            fund_info,
+            payout_info,
+            payout_token_account_info,
+            depositor_token_account_info,
+            mint_info,
+            token_program,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::token::{close_token_account_signed, token_balance, transfer_checked_signed};
+
+pub fn process_close_payout(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = ClosePayout::try_from_bytes(data)?;
+    let counter = u64::from_le_bytes(args.counter);
+
+    let [payout_info, payout_token_account_info, rent_payer_info, fund_info, fund_token_account_info, mint_info, token_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let payout = payout_info
+        .is_writable()?
+        .has_seeds(
+            &[PAYOUT, fund_info.key.as_ref(), &counter.to_le_bytes()],
+            &yoko_program_api::ID,
+        )?
+        .as_account::<Payout>(&yoko_program_api::ID)?;
+
+    // Anyone may close a fully claimed payout, since its rent only ever returns to the payer.
+    if payout.claimed_shares < payout.total_shares {
+        return Err(YokoProgramError::PayoutNotFullyClaimed.into());
+    }
+
+    rent_payer_info
+        .is_writable()?
+        .has_address(&payout.rent_payer)?;
+    mint_info.has_address(&payout.mint)?;
+    fund_token_account_info.is_writable()?.has_seeds(
+        &[
+            TOKEN_ACCOUNT,
+            fund_info.key.as_ref(),
+            mint_info.key.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
+    payout_token_account_info
+        .is_writable()?
+        .has_seeds(&[PAYOUT, payout_info.key.as_ref()], &yoko_program_api::ID)?;
+
+    // Claims empty the payout token account, so anything left was sent to it afterwards and would
+    // otherwise keep it from being closed.
+    let remaining = token_balance(payout_token_account_info)?;
+    if remaining > 0 {
+        transfer_checked_signed(
+            payout_info,
+            payout_token_account_info,
+            fund_token_account_info,
+            mint_info,
+            token_program,
+            remaining,
+            &[PAYOUT, fund_info.key.as_ref(), &counter.to_le_bytes()],
+        )?;
+    }
+
+    close_token_account_signed(
+        payout_token_account_info,
+        rent_payer_info,
+        mint_info,
+        payout_info,
+        token_program,
+        &[PAYOUT, fund_info.key.as_ref(), &counter.to_le_bytes()],
+    )?;
+
+    close_account(payout_info, rent_payer_info)?;
+
+    PayoutClosed {
+        fund: *fund_info.key,
+        payout: *payout_info.key,
+        counter,
+        reclaimed_amount: remaining,
+    }
+    .log();
+
+    Ok(())
+}
@@ -1,19 +1,38 @@
-use solana_program::{program::invoke, program_pack::Pack};
-use spl_associated_token_account::tools::account::create_pda_account;
-use spl_token::{instruction::initialize_account3, state::Account as SplTokenAccount};
 use steel::*;
-use sysvar::rent::Rent;
 use yoko_program_api::prelude::*;
 
+use crate::{pause::check_protocol_not_paused, token::create_token_account};
+
 pub fn process_create_fund(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
     let args = CreateFund::try_from_bytes(data)?;
-    let authority_fee = u64::from_le_bytes(args.authority_fee);
+    let id = u64::from_le_bytes(args.id);
+    let authority_fee_bps = u64::from_le_bytes(args.authority_fee_bps);
+
+    let hurdle_bps = u64::from_le_bytes(args.hurdle_bps);
+    let management_fee_bps = u64::from_le_bytes(args.management_fee_bps);
+    let max_swap_deviation_bps = u64::from_le_bytes(args.max_swap_deviation_bps);
+    let deposit_cap = u64::from_le_bytes(args.deposit_cap);
+    let min_deposit = u64::from_le_bytes(args.min_deposit);
+    let max_position_deposit = u64::from_le_bytes(args.max_position_deposit);
+    let fee_mode = FeeMode::try_from(args.fee_mode).map_err(|_| ProgramError::InvalidArgument)?;
+
+    if authority_fee_bps > MAX_AUTHORITY_FEE_BPS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if hurdle_bps > MAX_BPS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if management_fee_bps > MAX_MANAGEMENT_FEE_BPS {
+        return Err(ProgramError::InvalidArgument);
+    }
 
-    if authority_fee >= 100 {
+    if max_swap_deviation_bps > MAX_BPS {
         return Err(ProgramError::InvalidArgument);
     }
 
-    let [fund_info, authority_info, main_mint_info, main_token_account_info, token_program, system_program] =
+    let [fund_info, authority_info, main_mint_info, main_token_account_info, main_oracle_info, protocol_config_info, token_program, system_program] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -21,61 +40,71 @@ pub fn process_create_fund(accounts: &[AccountInfo<'_>], data: &[u8]) -> Program
 
     authority_info.is_signer()?;
 
-    fund_info
-        .is_writable()?
-        .has_seeds(&[FUND, authority_info.key.as_ref()], &yoko_program_api::ID)?;
+    check_protocol_not_paused(protocol_config_info)?;
 
-    let main_token_account = fund_token_account_pda(&fund_info.key, &main_mint_info.key);
-    if main_token_account_info.key != &main_token_account.0 {
-        return Err(ProgramError::InvalidSeeds);
-    }
+    fund_info.is_writable()?.has_seeds(
+        &[FUND, authority_info.key.as_ref(), &id.to_le_bytes()],
+        &yoko_program_api::ID,
+    )?;
+
+    let main_feed_id = PythPriceUpdate::load_feed_id(main_oracle_info)?;
+
+    let main_mint_data = unpack_mint(&main_mint_info.data.borrow())?;
 
     create_account::<Fund>(
         fund_info,
         system_program,
         authority_info,
         &yoko_program_api::ID,
-        &[FUND, authority_info.key.as_ref()],
+        &[FUND, authority_info.key.as_ref(), &id.to_le_bytes()],
     )?;
 
     let fund = fund_info.as_account_mut::<Fund>(&yoko_program_api::ID)?;
 
     fund.authority = *authority_info.key;
-    fund.total_deposited = 0;
+    fund.pending_authority = Pubkey::default();
+    fund.creator = *authority_info.key;
+    fund.id = id;
+    fund.total_shares = 0;
     fund.payouts_counter = 0;
-    fund.authority_fee = authority_fee;
+    fund.authority_fee_bps = authority_fee_bps;
+    fund.hurdle_bps = hurdle_bps;
+    fund.high_water_mark = NAV_PER_SHARE_SCALE;
+    fund.management_fee_bps = management_fee_bps;
+    fund.last_management_fee_accrual = Clock::get()?.unix_timestamp;
+    fund.max_swap_deviation_bps = max_swap_deviation_bps;
+    fund.total_deposited = 0;
+    fund.deposit_cap = deposit_cap;
+    fund.min_deposit = min_deposit;
+    fund.max_position_deposit = max_position_deposit;
+    fund.fee_mode = fee_mode.into();
     fund.main_mint = *main_mint_info.key;
     fund.other_mints = ArraySet::new();
+    fund.main_oracle = *main_oracle_info.key;
+    fund.main_feed_id = main_feed_id;
+    fund.main_decimals = main_mint_data.decimals;
 
-    let rent = Rent::get()?;
-    create_pda_account(
+    create_token_account(
         authority_info,
-        &rent,
-        SplTokenAccount::LEN,
-        token_program.key,
-        system_program,
         main_token_account_info,
+        main_mint_info,
+        fund_info.key,
+        token_program,
+        system_program,
         &[
-            &TOKEN_ACCOUNT[..],
+            TOKEN_ACCOUNT,
             fund_info.key.as_ref(),
             main_mint_info.key.as_ref(),
-            &[main_token_account.1],
         ],
     )?;
 
-    invoke(
-        &initialize_account3(
-            token_program.key,
-            main_token_account_info.key,
-            main_mint_info.key,
-            fund_info.key,
-        )?,
-        &[
-            main_token_account_info.clone(),
-            main_mint_info.clone(),
-            token_program.clone(),
-        ],
-    )?;
+    FundCreated {
+        fund: *fund_info.key,
+        authority: *authority_info.key,
+        main_mint: *main_mint_info.key,
+        params: *args,
+    }
+    .log();
 
     Ok(())
 }
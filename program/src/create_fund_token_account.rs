@@ -1,12 +1,10 @@
-use solana_program::{program::invoke, program_pack::Pack};
-use spl_associated_token_account::tools::account::create_pda_account;
-use spl_token::{instruction::initialize_account3, state::Account as SplTokenAccount};
 use steel::*;
-use sysvar::rent::Rent;
 use yoko_program_api::prelude::*;
 
+use crate::{mandate::check_mandate, pause::check_not_paused, token::create_token_account};
+
 pub fn process_create_fund_token_account(accounts: &[AccountInfo<'_>]) -> ProgramResult {
-    let [fund_info, authority_info, fund_token_account_info, mint_info, token_program, system_program] =
+    let [fund_info, authority_info, fund_token_account_info, mint_info, oracle_info, mandate_info, protocol_config_info, token_program, system_program] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -16,49 +14,46 @@ pub fn process_create_fund_token_account(accounts: &[AccountInfo<'_>]) -> Progra
 
     let fund = fund_info
         .is_writable()?
-        .has_seeds(&[FUND, authority_info.key.as_ref()], &yoko_program_api::ID)?
         .as_account_mut::<Fund>(&yoko_program_api::ID)?
         .assert_mut(|fund| fund.authority == *authority_info.key)?;
 
-    let inserted = fund.other_mints.insert(*mint_info.key);
+    check_not_paused(protocol_config_info, fund)?;
+    check_mandate(fund_info, fund, mandate_info, mint_info.key)?;
+
+    let feed_id = PythPriceUpdate::load_feed_id(oracle_info)?;
+
+    let mint_data = unpack_mint(&mint_info.data.borrow())?;
+
+    let inserted = fund.insert_other_mint(
+        *mint_info.key,
+        *oracle_info.key,
+        feed_id,
+        mint_data.decimals,
+    );
     if !inserted {
         return Err(YokoProgramError::ErrorInsertingOtherMint.into());
     }
 
-    let fund_token_account = fund_token_account_pda(&fund_info.key, mint_info.key);
-    if fund_token_account_info.key != &fund_token_account.0 {
-        return Err(ProgramError::InvalidSeeds);
-    }
-
-    let rent = Rent::get()?;
-    create_pda_account(
+    create_token_account(
         authority_info,
-        &rent,
-        SplTokenAccount::LEN,
-        token_program.key,
-        system_program,
         fund_token_account_info,
+        mint_info,
+        fund_info.key,
+        token_program,
+        system_program,
         &[
-            &TOKEN_ACCOUNT[..],
+            TOKEN_ACCOUNT,
             fund_info.key.as_ref(),
             mint_info.key.as_ref(),
-            &[fund_token_account.1],
         ],
     )?;
 
-    invoke(
-        &initialize_account3(
-            token_program.key,
-            fund_token_account_info.key,
-            mint_info.key,
-            fund_info.key,
-        )?,
-        &[
-            fund_token_account_info.clone(),
-            mint_info.clone(),
-            token_program.clone(),
-        ],
-    )?;
+    FundTokenAccountCreated {
+        fund: *fund_info.key,
+        mint: *mint_info.key,
+        token_account: *fund_token_account_info.key,
+    }
+    .log();
 
     Ok(())
 }
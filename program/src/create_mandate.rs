@@ -0,0 +1,63 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_create_mandate(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [fund_info, authority_info, mandate_info, system_program, mint_infos @ ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    // Depositors rely on the mandate, so it can only be set before anyone deposits.
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?
+        .assert_mut(|fund| fund.total_shares == 0)?
+        .assert_mut(|fund| !fund.has_mandate())?;
+
+    mandate_info
+        .is_writable()?
+        .has_seeds(&[MANDATE, fund_info.key.as_ref()], &yoko_program_api::ID)?;
+
+    create_account::<Mandate>(
+        mandate_info,
+        system_program,
+        authority_info,
+        &yoko_program_api::ID,
+        &[MANDATE, fund_info.key.as_ref()],
+    )?;
+
+    let mandate = mandate_info.as_account_mut::<Mandate>(&yoko_program_api::ID)?;
+    mandate.fund = *fund_info.key;
+    mandate.mints = ArraySet::new();
+
+    for mint_info in mint_infos {
+        if !is_token_program(mint_info.owner) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        unpack_mint(&mint_info.data.borrow())?;
+
+        if !mandate.mints.insert(*mint_info.key) {
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+
+    // Mints the fund already holds must be covered by its own mandate.
+    for mint in fund.other_mints.iter() {
+        if !mandate.mints.contains(mint) {
+            return Err(YokoProgramError::MintNotInMandate.into());
+        }
+    }
+
+    fund.has_mandate = 1;
+
+    MandateCreated {
+        fund: *fund_info.key,
+        mandate: *mandate_info.key,
+    }
+    .log();
+
+    Ok(())
+}
@@ -1,161 +1,260 @@
-use std::str::FromStr;
-
-use solana_program::program::invoke;
-use spl_associated_token_account::{
-    solana_program::program_pack::Pack, tools::account::create_pda_account,
-};
-use spl_token::{instruction::initialize_account3, state::Account as SplTokenAccount};
 use steel::*;
-use sysvar::rent::Rent;
 use yoko_program_api::prelude::*;
 
-const PROTOCOL_FEE: u64 = 1; // 1%
-const PROTOCOL_FEE_TOKEN_ACCOUNT_OWNER: &str = "H61JjSDPCwvAs1k2vaPAX6d917Pu4dPWykcexvXXzGph";
+use crate::{
+    nav::fund_value,
+    token::{create_token_account, token_account, transfer_checked_signed},
+};
 
 pub fn process_create_payout(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
     let args = CreatePayout::try_from_bytes(data)?;
     let amount = u64::from_le_bytes(args.amount);
+    let claim_period = i64::from_le_bytes(args.claim_period);
 
-    let [fund_authority_info, fund_authority_token_account_info, fund_info, fund_main_token_account_info, payout_info, payout_main_token_account_info, main_mint_info, protocol_fee_token_account_info, token_program, system_program] =
+    let [fund_authority_info, fund_authority_token_account_info, fund_info, fund_main_token_account_info, payout_info, payout_main_token_account_info, main_mint_info, protocol_config_info, protocol_fee_token_account_info, token_program, system_program, valuation_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    let protocol_fee_token_account_data =
-        SplTokenAccount::unpack(&protocol_fee_token_account_info.data.borrow())?;
+    fund_info
+        .as_account::<Fund>(&yoko_program_api::ID)?
+        .assert(|fund| fund.main_mint == *main_mint_info.key)?;
+
+    create_payout(
+        &PayoutAccounts {
+            fund_authority: fund_authority_info,
+            fund_authority_token_account: fund_authority_token_account_info,
+            fund: fund_info,
+            fund_main_token_account: fund_main_token_account_info,
+            fund_token_account: fund_main_token_account_info,
+            payout: payout_info,
+            payout_token_account: payout_main_token_account_info,
+            mint: main_mint_info,
+            protocol_config: protocol_config_info,
+            protocol_fee_token_account: protocol_fee_token_account_info,
+            token_program,
+            system_program,
+            valuation_accounts,
+        },
+        amount,
+        claim_period,
+        || Ok(amount),
+    )
+}
+
+pub struct PayoutAccounts<'a, 'info> {
+    pub fund_authority: &'a AccountInfo<'info>,
+    pub fund_authority_token_account: &'a AccountInfo<'info>,
+    pub fund: &'a AccountInfo<'info>,
+    pub fund_main_token_account: &'a AccountInfo<'info>,
+    /// Fund token account of the payout mint, which the payout is paid from.
+    pub fund_token_account: &'a AccountInfo<'info>,
+    pub payout: &'a AccountInfo<'info>,
+    pub payout_token_account: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub protocol_config: &'a AccountInfo<'info>,
+    pub protocol_fee_token_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub valuation_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Creates the next payout of `amount` of `accounts.mint` after the protocol and authority fees,
+/// claimable for `claim_period` seconds or indefinitely when it is zero. `amount_value` returns the
+/// worth of `amount` in main mint base units and is only called when the fund charges a
+/// performance fee.
+pub fn create_payout(
+    accounts: &PayoutAccounts<'_, '_>,
+    amount: u64,
+    claim_period: i64,
+    amount_value: impl FnOnce() -> Result<u64, ProgramError>,
+) -> ProgramResult {
+    let protocol_config = accounts
+        .protocol_config
+        .has_seeds(&[PROTOCOL_CONFIG], &yoko_program_api::ID)?
+        .as_account::<ProtocolConfig>(&yoko_program_api::ID)?;
+
+    if protocol_config.is_paused() {
+        return Err(YokoProgramError::ProtocolPaused.into());
+    }
 
-    if protocol_fee_token_account_data.owner
-        != Pubkey::from_str(PROTOCOL_FEE_TOKEN_ACCOUNT_OWNER).unwrap()
-    {
+    let protocol_fee_token_account_data = token_account(accounts.protocol_fee_token_account)?;
+
+    if protocol_fee_token_account_data.owner != protocol_config.fee_recipient {
         return Err(ProgramError::InvalidAccountData);
     }
 
-    if protocol_fee_token_account_data.mint != *main_mint_info.key {
+    if protocol_fee_token_account_data.mint != *accounts.mint.key {
         return Err(ProgramError::InvalidAccountData);
     }
 
-    fund_authority_info.is_signer()?.is_writable()?;
+    if claim_period < 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    accounts.fund_authority.is_signer()?.is_writable()?;
 
-    let fund = fund_info
+    let fund = accounts
+        .fund
         .is_writable()?
-        .has_seeds(
-            &[FUND, fund_authority_info.key.as_ref()],
-            &yoko_program_api::ID,
-        )?
         .as_account_mut::<Fund>(&yoko_program_api::ID)?
-        .assert_mut(|fund| fund.authority == *fund_authority_info.key)?
-        .assert_mut(|fund| fund.main_mint == *main_mint_info.key)?;
+        .assert_mut(|fund| fund.authority == *accounts.fund_authority.key)?;
+
+    if fund.is_paused() {
+        return Err(YokoProgramError::FundPaused.into());
+    }
+
+    // Without shares nobody could ever claim the payout.
+    if fund.total_shares == 0 {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
+    accounts.fund_token_account.has_seeds(
+        &[
+            TOKEN_ACCOUNT,
+            accounts.fund.key.as_ref(),
+            accounts.mint.key.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
 
     fund.payouts_counter = fund
         .payouts_counter
         .checked_add(1)
         .ok_or(ProgramError::InvalidArgument)?;
 
-    payout_info.is_writable()?.has_seeds(
+    accounts.payout.is_writable()?.has_seeds(
         &[
             PAYOUT,
-            fund_info.key.as_ref(),
+            accounts.fund.key.as_ref(),
             &fund.payouts_counter.to_le_bytes(),
         ],
         &yoko_program_api::ID,
     )?;
 
     create_account::<Payout>(
-        payout_info,
-        system_program,
-        fund_authority_info,
+        accounts.payout,
+        accounts.system_program,
+        accounts.fund_authority,
         &yoko_program_api::ID,
         &[
             PAYOUT,
-            fund_info.key.as_ref(),
+            accounts.fund.key.as_ref(),
             &fund.payouts_counter.to_le_bytes(),
         ],
     )?;
 
-    let payout = payout_info.as_account_mut::<Payout>(&yoko_program_api::ID)?;
-    payout.total_deposited = fund.total_deposited;
-
-    let payout_main_token_account = payout_token_account_pda(payout_info.key);
-    if payout_main_token_account_info.key != &payout_main_token_account.0 {
-        return Err(ProgramError::InvalidSeeds);
-    }
+    let payout = accounts
+        .payout
+        .as_account_mut::<Payout>(&yoko_program_api::ID)?;
+    payout.total_shares = fund.total_shares;
+    payout.mint = *accounts.mint.key;
+    payout.rent_payer = *accounts.fund_authority.key;
+    payout.claim_deadline = if claim_period > 0 {
+        Clock::get()?
+            .unix_timestamp
+            .checked_add(claim_period)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        0
+    };
 
-    let rent = Rent::get()?;
-    create_pda_account(
-        fund_authority_info,
-        &rent,
-        SplTokenAccount::LEN,
-        token_program.key,
-        system_program,
-        payout_main_token_account_info,
-        &[
-            &PAYOUT[..],
-            payout_info.key.as_ref(),
-            &[payout_main_token_account.1],
-        ],
+    create_token_account(
+        accounts.fund_authority,
+        accounts.payout_token_account,
+        accounts.mint,
+        accounts.payout.key,
+        accounts.token_program,
+        accounts.system_program,
+        &[PAYOUT, accounts.payout.key.as_ref()],
     )?;
 
-    invoke(
-        &initialize_account3(
-            token_program.key,
-            payout_main_token_account_info.key,
-            main_mint_info.key,
-            payout_info.key,
-        )?,
-        &[
-            payout_main_token_account_info.clone(),
-            main_mint_info.clone(),
-            token_program.clone(),
-        ],
-    )?;
+    let authority_fee = if fund.has_performance_fee() {
+        let value = fund_value(
+            accounts.fund,
+            fund,
+            accounts.fund_main_token_account,
+            accounts.valuation_accounts,
+        )?;
+        let current_nav_per_share = nav_per_share(value, fund.total_shares)?;
+        let performance_fee = fund.performance_fee(value)?;
+        let amount_value = amount_value()?;
 
-    let authority_amount = amount
-        .checked_mul(fund.authority_fee)
-        .ok_or(ProgramError::InvalidArgument)?
-        .checked_div(100)
-        .ok_or(ProgramError::InvalidArgument)?;
+        // A charged fee crystallizes the current net asset value per share as the new mark. The
+        // payout then returns value to depositors, which must not count as a loss to recover.
+        let high_water_mark = if performance_fee > 0 {
+            current_nav_per_share
+        } else {
+            fund.high_water_mark
+        };
+        let distributed_per_share = nav_per_share(amount_value, fund.total_shares)?;
+        fund.high_water_mark = high_water_mark.saturating_sub(distributed_per_share);
 
-    let protocol_fee_amount = amount
-        .checked_mul(PROTOCOL_FEE)
-        .ok_or(ProgramError::InvalidArgument)?
-        .checked_div(100)
-        .ok_or(ProgramError::InvalidArgument)?;
+        // The fee is owed in main mint value and taken in the payout mint at the same rate.
+        if amount_value == 0 {
+            0
+        } else {
+            u64::try_from(performance_fee as u128 * amount as u128 / amount_value as u128)
+                .map_err(|_| ProgramError::ArithmeticOverflow)?
+        }
+    } else {
+        bps_of(amount, fund.authority_fee_bps)?
+    };
 
-    let rest_amount = amount
-        .checked_sub(authority_amount)
-        .ok_or(ProgramError::InvalidArgument)?
-        .checked_sub(protocol_fee_amount)
-        .ok_or(ProgramError::InvalidArgument)?;
+    let (protocol_fee_amount, authority_amount, rest_amount) =
+        split_payout(amount, protocol_config.protocol_fee_bps, authority_fee)?;
+
+    // Only a performance fee can exceed what the protocol fee leaves. The high-water mark already
+    // moved as if it was charged in full, so the payout has to be large enough to carry it.
+    if authority_amount < authority_fee {
+        return Err(YokoProgramError::PayoutBelowPerformanceFee.into());
+    }
 
-    transfer_signed(
-        fund_info,
-        fund_main_token_account_info,
-        fund_authority_token_account_info,
-        token_program,
+    transfer_checked_signed(
+        accounts.fund,
+        accounts.fund_token_account,
+        accounts.fund_authority_token_account,
+        accounts.mint,
+        accounts.token_program,
         authority_amount,
-        &[FUND, fund_authority_info.key.as_ref()],
+        &[FUND, fund.creator.as_ref(), &fund.id.to_le_bytes()],
     )?;
 
-    transfer_signed(
-        fund_info,
-        fund_main_token_account_info,
-        protocol_fee_token_account_info,
-        token_program,
+    transfer_checked_signed(
+        accounts.fund,
+        accounts.fund_token_account,
+        accounts.protocol_fee_token_account,
+        accounts.mint,
+        accounts.token_program,
         protocol_fee_amount,
-        &[FUND, fund_authority_info.key.as_ref()],
+        &[FUND, fund.creator.as_ref(), &fund.id.to_le_bytes()],
     )?;
 
-    transfer_signed(
-        fund_info,
-        fund_main_token_account_info,
-        payout_main_token_account_info,
-        token_program,
+    // Claims are paid from what the payout account received, net of any transfer fee.
+    let payout_amount = transfer_checked_signed(
+        accounts.fund,
+        accounts.fund_token_account,
+        accounts.payout_token_account,
+        accounts.mint,
+        accounts.token_program,
         rest_amount,
-        &[FUND, fund_authority_info.key.as_ref()],
+        &[FUND, fund.creator.as_ref(), &fund.id.to_le_bytes()],
     )?;
+    payout.amount_transferred_on_creation = payout_amount;
 
-    payout.amount_transferred_on_creation = rest_amount;
+    PayoutCreated {
+        fund: *accounts.fund.key,
+        payout: *accounts.payout.key,
+        mint: *accounts.mint.key,
+        counter: fund.payouts_counter,
+        amount,
+        protocol_fee: protocol_fee_amount,
+        authority_fee: authority_amount,
+        payout_amount,
+        claim_deadline: payout.claim_deadline,
+    }
+    .log();
 
     Ok(())
 }
@@ -0,0 +1,65 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    create_payout::{create_payout, PayoutAccounts},
+    deposit_in_kind::credited_value,
+};
+
+pub fn process_create_payout_in_kind(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = CreatePayoutInKind::try_from_bytes(data)?;
+    let amount = u64::from_le_bytes(args.amount);
+    let claim_period = i64::from_le_bytes(args.claim_period);
+
+    let [fund_authority_info, fund_authority_token_account_info, fund_info, fund_main_token_account_info, fund_token_account_info, payout_info, payout_token_account_info, mint_info, oracle_info, main_oracle_info, protocol_config_info, protocol_fee_token_account_info, token_program, system_program, valuation_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let fund = fund_info.as_account::<Fund>(&yoko_program_api::ID)?;
+
+    // Main mint payouts go through `CreatePayout`.
+    if *mint_info.key == fund.main_mint {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (oracle, feed_id, decimals) = fund
+        .asset(mint_info.key)
+        .ok_or(YokoProgramError::InvalidAccount)?;
+    let main_decimals = fund.main_decimals;
+    let main_feed_id = fund.main_feed_id;
+
+    oracle_info.has_address(&oracle)?;
+    main_oracle_info.has_address(&fund.main_oracle)?;
+
+    create_payout(
+        &PayoutAccounts {
+            fund_authority: fund_authority_info,
+            fund_authority_token_account: fund_authority_token_account_info,
+            fund: fund_info,
+            fund_main_token_account: fund_main_token_account_info,
+            fund_token_account: fund_token_account_info,
+            payout: payout_info,
+            payout_token_account: payout_token_account_info,
+            mint: mint_info,
+            protocol_config: protocol_config_info,
+            protocol_fee_token_account: protocol_fee_token_account_info,
+            token_program,
+            system_program,
+            valuation_accounts,
+        },
+        amount,
+        claim_period,
+        || {
+            credited_value::<PythPriceUpdate>(
+                amount,
+                decimals,
+                oracle_info,
+                &feed_id,
+                main_decimals,
+                main_oracle_info,
+                &main_feed_id,
+            )
+        },
+    )
+}
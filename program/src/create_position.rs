@@ -1,8 +1,12 @@
 use steel::*;
 use yoko_program_api::prelude::*;
 
-pub fn process_create_position(accounts: &[AccountInfo<'_>]) -> ProgramResult {
-    let [position_info, fund_info, authority_info, system_program] = accounts else {
+use crate::{access::check_access, pause::check_not_paused};
+
+pub fn process_create_position(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let [position_info, fund_info, authority_info, protocol_config_info, system_program, access_accounts @ ..] =
+        accounts
+    else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -17,6 +21,11 @@ pub fn process_create_position(accounts: &[AccountInfo<'_>]) -> ProgramResult {
         &yoko_program_api::ID,
     )?;
 
+    let fund = fund_info.as_account::<Fund>(&yoko_program_api::ID)?;
+
+    check_not_paused(protocol_config_info, fund)?;
+    check_access(fund_info, fund, authority_info, access_accounts, data)?;
+
     create_account::<Position>(
         position_info,
         system_program,
@@ -29,13 +38,22 @@ pub fn process_create_position(accounts: &[AccountInfo<'_>]) -> ProgramResult {
         ],
     )?;
 
-    let fund = fund_info.as_account::<Fund>(&yoko_program_api::ID)?;
     let position = position_info.as_account_mut::<Position>(&yoko_program_api::ID)?;
 
     position.authority = *authority_info.key;
     position.fund = *fund_info.key;
-    position.deposited = 0;
+    position.shares = 0;
     position.payouts_counter = fund.payouts_counter;
+    position.deposited = 0;
+    position.last_deposit_ts = 0;
+    position.redemption_requested_at = 0;
+
+    PositionCreated {
+        fund: *fund_info.key,
+        position: *position_info.key,
+        authority: *authority_info.key,
+    }
+    .log();
 
     Ok(())
 }
@@ -1,20 +1,86 @@
-use solana_program::program_pack::Pack;
-use spl_token::state::Account as SplTokenAccount;
 use steel::*;
 use yoko_program_api::prelude::*;
 
+use crate::{
+    accrue_management_fee::{accrue_management_fee, ManagementFeeAccounts},
+    nav::fund_value,
+    pause::check_not_paused,
+    token::{token_account, transfer_checked},
+};
+
 pub fn process_deposit(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
     let args = Deposit::try_from_bytes(data)?;
     let amount = u64::from_le_bytes(args.amount);
 
-    let [position_info, fund_info, fund_main_token_account_info, depositor_authority_info, depositor_token_account_info, token_program] =
+    let [position_info, fund_info, fund_main_token_account_info, depositor_authority_info, depositor_token_account_info, main_mint_info, protocol_config_info, token_program, fund_authority_token_account_info, valuation_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
+    let (position, fund, value) = prepare_deposit(
+        position_info,
+        fund_info,
+        fund_main_token_account_info,
+        depositor_authority_info,
+        protocol_config_info,
+        &ManagementFeeAccounts {
+            fund: fund_info,
+            fund_main_token_account: fund_main_token_account_info,
+            fund_authority_token_account: fund_authority_token_account_info,
+            main_mint: main_mint_info,
+            token_program,
+        },
+        valuation_accounts,
+        amount,
+    )?;
+
+    // The fund receives less than `amount` when the main mint charges a transfer fee.
+    let received = transfer_checked(
+        depositor_authority_info,
+        depositor_token_account_info,
+        fund_main_token_account_info,
+        main_mint_info,
+        token_program,
+        amount,
+    )?;
+
+    let shares = issue_shares(position, fund, value, received)?;
+
+    Deposited {
+        fund: *fund_info.key,
+        position: *position_info.key,
+        mint: fund.main_mint,
+        amount: received,
+        value: received,
+        shares,
+        total_shares: fund.total_shares,
+    }
+    .log();
+
+    Ok(())
+}
+
+/// Validates a deposit of `amount`, collects the pending management fee and returns the position,
+/// the fund and the fund value before the deposit lands. Deposit limits are checked by
+/// `issue_shares` on the credited value.
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_deposit<'a, 'info>(
+    position_info: &'a AccountInfo<'info>,
+    fund_info: &'a AccountInfo<'info>,
+    fund_main_token_account_info: &'a AccountInfo<'info>,
+    depositor_authority_info: &'a AccountInfo<'info>,
+    protocol_config_info: &'a AccountInfo<'info>,
+    fee_accounts: &ManagementFeeAccounts<'_, 'info>,
+    valuation_accounts: &'a [AccountInfo<'info>],
+    amount: u64,
+) -> Result<(&'a mut Position, &'a mut Fund, u64), ProgramError> {
     depositor_authority_info.is_signer()?;
 
+    if amount == 0 {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
     position_info.has_seeds(
         &[
             POSITION,
@@ -29,23 +95,15 @@ pub fn process_deposit(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResu
         .assert_mut(|position| position.authority == *depositor_authority_info.key)?
         .assert_mut(|position| position.fund == *fund_info.key)?;
 
-    position.deposited = position
-        .deposited
-        .checked_add(amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    let fund_main_token_account_data =
-        SplTokenAccount::unpack(&fund_main_token_account_info.data.borrow())?;
+    let fund_main_token_account_data = token_account(fund_main_token_account_info)?;
 
     let fund = fund_info
         .as_account_mut::<Fund>(&yoko_program_api::ID)?
         .assert_mut(|fund| fund.main_mint == fund_main_token_account_data.mint)?
         .assert_mut(|fund| fund.payouts_counter == position.payouts_counter)?;
 
-    fund.total_deposited = fund
-        .total_deposited
-        .checked_add(amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    check_not_paused(protocol_config_info, fund)?;
+    fee_accounts.main_mint.has_address(&fund.main_mint)?;
 
     fund_main_token_account_info.has_seeds(
         &[
@@ -56,13 +114,92 @@ pub fn process_deposit(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResu
         &yoko_program_api::ID,
     )?;
 
-    transfer(
-        depositor_authority_info,
-        depositor_token_account_info,
-        fund_main_token_account_info,
-        token_program,
-        amount,
-    )?;
+    // Shares are minted at the net asset value per share before the deposit lands, once the
+    // management fee owed by the existing shares is taken out of it.
+    let value = if fund.total_shares == 0 {
+        0
+    } else {
+        let value = fund_value(
+            fund_info,
+            fund,
+            fund_main_token_account_info,
+            valuation_accounts,
+        )?;
+
+        value - accrue_management_fee(fee_accounts, fund, value)?
+    };
+
+    Ok((position, fund, value))
+}
+
+/// Mints shares for `received`, the deposit's worth in main mint base units, at the fund `value`
+/// before the deposit, and returns them.
+pub fn issue_shares(
+    position: &mut Position,
+    fund: &mut Fund,
+    value: u64,
+    received: u64,
+) -> Result<u64, ProgramError> {
+    check_deposit_limits(fund, position, received)?;
+
+    let shares = if fund.total_shares == 0 {
+        // The first shares are minted 1:1, so the high-water mark starts over, and no management
+        // fee is owed for the time the fund had no depositors.
+        fund.high_water_mark = NAV_PER_SHARE_SCALE;
+        fund.last_management_fee_accrual = Clock::get()?.unix_timestamp;
+        received
+    } else {
+        let shares = (received as u128)
+            .checked_mul(fund.total_shares as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .checked_div(value as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        u64::try_from(shares).map_err(|_| ProgramError::ArithmeticOverflow)?
+    };
+
+    if shares == 0 {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
+    position.shares = position
+        .shares
+        .checked_add(shares)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    fund.total_shares = fund
+        .total_shares
+        .checked_add(shares)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    position.deposited = position
+        .deposited
+        .checked_add(received)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    position.last_deposit_ts = Clock::get()?.unix_timestamp;
+
+    fund.total_deposited = fund
+        .total_deposited
+        .checked_add(received)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(shares)
+}
+
+fn check_deposit_limits(fund: &Fund, position: &Position, amount: u64) -> ProgramResult {
+    if amount < fund.min_deposit {
+        return Err(YokoProgramError::DepositTooSmall.into());
+    }
+
+    if fund.deposit_cap != 0 && fund.total_deposited.saturating_add(amount) > fund.deposit_cap {
+        return Err(YokoProgramError::DepositCapExceeded.into());
+    }
+
+    if fund.max_position_deposit != 0
+        && position.deposited.saturating_add(amount) > fund.max_position_deposit
+    {
+        return Err(YokoProgramError::PositionLimitExceeded.into());
+    }
 
     Ok(())
 }
@@ -0,0 +1,107 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    accrue_management_fee::ManagementFeeAccounts,
+    deposit::{issue_shares, prepare_deposit},
+    token::transfer_checked,
+};
+
+pub fn process_deposit_in_kind(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = DepositInKind::try_from_bytes(data)?;
+    let amount = u64::from_le_bytes(args.amount);
+
+    let [position_info, fund_info, fund_main_token_account_info, depositor_authority_info, depositor_token_account_info, fund_token_account_info, mint_info, oracle_info, main_oracle_info, protocol_config_info, token_program, fund_authority_token_account_info, main_mint_info, main_token_program, valuation_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (position, fund, value) = prepare_deposit(
+        position_info,
+        fund_info,
+        fund_main_token_account_info,
+        depositor_authority_info,
+        protocol_config_info,
+        &ManagementFeeAccounts {
+            fund: fund_info,
+            fund_main_token_account: fund_main_token_account_info,
+            fund_authority_token_account: fund_authority_token_account_info,
+            main_mint: main_mint_info,
+            token_program: main_token_program,
+        },
+        valuation_accounts,
+        amount,
+    )?;
+
+    // Only mints the fund already holds, so that their oracle was chosen by the fund authority.
+    if *mint_info.key == fund.main_mint {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (oracle, feed_id, decimals) = fund
+        .asset(mint_info.key)
+        .ok_or(YokoProgramError::InvalidAccount)?;
+
+    oracle_info.has_address(&oracle)?;
+    main_oracle_info.has_address(&fund.main_oracle)?;
+    fund_token_account_info.has_seeds(
+        &[
+            TOKEN_ACCOUNT,
+            fund_info.key.as_ref(),
+            mint_info.key.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
+
+    let received = transfer_checked(
+        depositor_authority_info,
+        depositor_token_account_info,
+        fund_token_account_info,
+        mint_info,
+        token_program,
+        amount,
+    )?;
+
+    let credited = credited_value::<PythPriceUpdate>(
+        received,
+        decimals,
+        oracle_info,
+        &feed_id,
+        fund.main_decimals,
+        main_oracle_info,
+        &fund.main_feed_id,
+    )?;
+
+    let shares = issue_shares(position, fund, value, credited)?;
+
+    Deposited {
+        fund: *fund_info.key,
+        position: *position_info.key,
+        mint: *mint_info.key,
+        amount: received,
+        value: credited,
+        shares,
+        total_shares: fund.total_shares,
+    }
+    .log();
+
+    Ok(())
+}
+
+/// Worth of `amount` base units of a mint with `decimals` in main mint base units, at the oracle
+/// prices of `feed_id` and `main_feed_id`.
+pub fn credited_value<F: PriceFeed>(
+    amount: u64,
+    decimals: u8,
+    oracle_info: &AccountInfo<'_>,
+    feed_id: &[u8; 32],
+    main_decimals: u8,
+    main_oracle_info: &AccountInfo<'_>,
+    main_feed_id: &[u8; 32],
+) -> Result<u64, ProgramError> {
+    let now = Clock::get()?.unix_timestamp;
+    let price = F::load_price(oracle_info, feed_id, now, MAX_PRICE_AGE)?;
+    let main_price = F::load_price(main_oracle_info, main_feed_id, now, MAX_PRICE_AGE)?;
+
+    convert_amount(amount, decimals, &price, main_decimals, &main_price)
+}
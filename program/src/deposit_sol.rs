@@ -0,0 +1,90 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    accrue_management_fee::ManagementFeeAccounts,
+    deposit::{issue_shares, prepare_deposit},
+    token::transfer_checked_signed,
+    wsol::{close_wsol_account, create_wsol_account},
+};
+
+pub fn process_deposit_sol(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = DepositSol::try_from_bytes(data)?;
+    let amount = u64::from_le_bytes(args.amount);
+
+    let [position_info, fund_info, fund_main_token_account_info, depositor_authority_info, wsol_account_info, main_mint_info, protocol_config_info, token_program, system_program, fund_authority_token_account_info, valuation_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (position, fund, value) = prepare_deposit(
+        position_info,
+        fund_info,
+        fund_main_token_account_info,
+        depositor_authority_info,
+        protocol_config_info,
+        &ManagementFeeAccounts {
+            fund: fund_info,
+            fund_main_token_account: fund_main_token_account_info,
+            fund_authority_token_account: fund_authority_token_account_info,
+            main_mint: main_mint_info,
+            token_program,
+        },
+        valuation_accounts,
+        amount,
+    )?;
+
+    if fund.main_mint != spl_token::native_mint::ID {
+        return Err(YokoProgramError::NotNativeMint.into());
+    }
+
+    create_wsol_account(
+        depositor_authority_info,
+        wsol_account_info,
+        position_info,
+        main_mint_info,
+        token_program,
+        system_program,
+        amount,
+    )?;
+
+    let position_seeds = [
+        POSITION,
+        fund_info.key.as_ref(),
+        depositor_authority_info.key.as_ref(),
+    ];
+
+    let received = transfer_checked_signed(
+        position_info,
+        wsol_account_info,
+        fund_main_token_account_info,
+        main_mint_info,
+        token_program,
+        amount,
+        &position_seeds,
+    )?;
+
+    close_wsol_account(
+        wsol_account_info,
+        position_info,
+        depositor_authority_info,
+        token_program,
+        &position_seeds,
+    )?;
+
+    let shares = issue_shares(position, fund, value, received)?;
+
+    Deposited {
+        fund: *fund_info.key,
+        position: *position_info.key,
+        mint: fund.main_mint,
+        amount: received,
+        value: received,
+        shares,
+        total_shares: fund.total_shares,
+    }
+    .log();
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_initialize_protocol(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = InitializeProtocol::try_from_bytes(data)?;
+    let protocol_fee_bps = u64::from_le_bytes(args.protocol_fee_bps);
+
+    if protocol_fee_bps > MAX_PROTOCOL_FEE_BPS {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
+    let [protocol_config_info, admin_info, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    admin_info
+        .is_signer()?
+        .is_writable()?
+        .has_address(&INITIAL_PROTOCOL_ADMIN)?;
+
+    protocol_config_info
+        .is_writable()?
+        .has_seeds(&[PROTOCOL_CONFIG], &yoko_program_api::ID)?;
+
+    create_account::<ProtocolConfig>(
+        protocol_config_info,
+        system_program,
+        admin_info,
+        &yoko_program_api::ID,
+        &[PROTOCOL_CONFIG],
+    )?;
+
+    let protocol_config =
+        protocol_config_info.as_account_mut::<ProtocolConfig>(&yoko_program_api::ID)?;
+
+    protocol_config.admin = *admin_info.key;
+    protocol_config.fee_recipient = args.fee_recipient;
+    protocol_config.protocol_fee_bps = protocol_fee_bps;
+    protocol_config.paused = 0;
+    protocol_config.swap_programs = [Pubkey::default(); MAX_SWAP_PROGRAMS];
+    protocol_config.swap_programs[0] = JUPITER_PROGRAM_ID;
+
+    ProtocolUpdated {
+        admin: protocol_config.admin,
+        fee_recipient: protocol_config.fee_recipient,
+        protocol_fee_bps: protocol_config.protocol_fee_bps,
+        paused: protocol_config.paused,
+        _padding: [0; 7],
+    }
+    .log();
+
+    Ok(())
+}
@@ -1,31 +1,83 @@
+mod accept_authority;
+mod access;
+mod accrue_management_fee;
+mod add_to_allowlist;
 mod claim_payout;
+mod claim_payout_sol;
+mod claim_payouts;
+mod close_payout;
 mod create_fund;
 mod create_fund_token_account;
+mod create_mandate;
 mod create_payout;
+mod create_payout_in_kind;
 mod create_position;
 mod deposit;
+mod deposit_in_kind;
+mod deposit_sol;
+mod initialize_protocol;
+mod mandate;
+mod nav;
+mod pause;
+mod propose_authority;
+mod reclaim_payout;
+mod remove_from_allowlist;
+mod request_redemption;
+mod set_access_policy;
+mod set_deposit_limits;
+mod set_fund_paused;
+mod set_redemption_rules;
+mod set_risk_limits;
+mod set_swap_program;
 mod swap;
+mod token;
+mod update_protocol;
+mod withdraw;
+mod wsol;
 
+use accept_authority::*;
+use accrue_management_fee::*;
+use add_to_allowlist::*;
 use claim_payout::*;
+use claim_payout_sol::*;
+use claim_payouts::*;
+use close_payout::*;
 use create_fund::*;
 use create_fund_token_account::*;
+use create_mandate::*;
 use create_payout::*;
+use create_payout_in_kind::*;
 use create_position::*;
 use deposit::*;
+use deposit_in_kind::*;
+use deposit_sol::*;
+use initialize_protocol::*;
+use propose_authority::*;
+use reclaim_payout::*;
+use remove_from_allowlist::*;
+use request_redemption::*;
+use set_access_policy::*;
+use set_deposit_limits::*;
+use set_fund_paused::*;
+use set_redemption_rules::*;
+use set_risk_limits::*;
+use set_swap_program::*;
 use steel::*;
 use swap::*;
+use update_protocol::*;
+use withdraw::*;
 use yoko_program_api::prelude::*;
 
-pub fn process_instruction<'a>(
+pub fn process_instruction(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
+    accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
     let (ix, data) = parse_instruction(&yoko_program_api::ID, program_id, data)?;
 
     match ix {
         YokoProgramInstruction::CreateFund => process_create_fund(accounts, data)?,
-        YokoProgramInstruction::CreatePosition => process_create_position(accounts)?,
+        YokoProgramInstruction::CreatePosition => process_create_position(accounts, data)?,
         YokoProgramInstruction::Deposit => process_deposit(accounts, data)?,
         YokoProgramInstruction::CreatePayout => process_create_payout(accounts, data)?,
         YokoProgramInstruction::ClaimPayout => process_claim_payout(accounts)?,
@@ -33,6 +85,31 @@ pub fn process_instruction<'a>(
         YokoProgramInstruction::CreateFundTokenAccount => {
             process_create_fund_token_account(accounts)?
         }
+        YokoProgramInstruction::Withdraw => process_withdraw(accounts, data)?,
+        YokoProgramInstruction::ClaimPayouts => process_claim_payouts(accounts)?,
+        YokoProgramInstruction::ProposeAuthority => process_propose_authority(accounts)?,
+        YokoProgramInstruction::AcceptAuthority => process_accept_authority(accounts)?,
+        YokoProgramInstruction::InitializeProtocol => process_initialize_protocol(accounts, data)?,
+        YokoProgramInstruction::UpdateProtocol => process_update_protocol(accounts, data)?,
+        YokoProgramInstruction::AccrueManagementFee => process_accrue_management_fee(accounts)?,
+        YokoProgramInstruction::SetSwapProgram => process_set_swap_program(accounts, data)?,
+        YokoProgramInstruction::CreateMandate => process_create_mandate(accounts)?,
+        YokoProgramInstruction::SetRiskLimits => process_set_risk_limits(accounts, data)?,
+        YokoProgramInstruction::SetDepositLimits => process_set_deposit_limits(accounts, data)?,
+        YokoProgramInstruction::SetAccessPolicy => process_set_access_policy(accounts, data)?,
+        YokoProgramInstruction::AddToAllowlist => process_add_to_allowlist(accounts)?,
+        YokoProgramInstruction::RemoveFromAllowlist => process_remove_from_allowlist(accounts)?,
+        YokoProgramInstruction::SetRedemptionRules => process_set_redemption_rules(accounts, data)?,
+        YokoProgramInstruction::RequestRedemption => process_request_redemption(accounts)?,
+        YokoProgramInstruction::SetFundPaused => process_set_fund_paused(accounts, data)?,
+        YokoProgramInstruction::DepositSol => process_deposit_sol(accounts, data)?,
+        YokoProgramInstruction::ClaimPayoutSol => process_claim_payout_sol(accounts)?,
+        YokoProgramInstruction::DepositInKind => process_deposit_in_kind(accounts, data)?,
+        YokoProgramInstruction::CreatePayoutInKind => {
+            process_create_payout_in_kind(accounts, data)?
+        }
+        YokoProgramInstruction::ClosePayout => process_close_payout(accounts, data)?,
+        YokoProgramInstruction::ReclaimPayout => process_reclaim_payout(accounts, data)?,
     }
 
     Ok(())
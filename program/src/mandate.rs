@@ -0,0 +1,25 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+/// Fails unless the fund may hold `mint`. The main mint is always allowed, and funds without a
+/// mandate may hold any mint.
+pub fn check_mandate(
+    fund_info: &AccountInfo<'_>,
+    fund: &Fund,
+    mandate_info: &AccountInfo<'_>,
+    mint: &Pubkey,
+) -> ProgramResult {
+    if !fund.has_mandate() || *mint == fund.main_mint {
+        return Ok(());
+    }
+
+    let mandate = mandate_info
+        .has_seeds(&[MANDATE, fund_info.key.as_ref()], &yoko_program_api::ID)?
+        .as_account::<Mandate>(&yoko_program_api::ID)?;
+
+    if !mandate.mints.contains(mint) {
+        return Err(YokoProgramError::MintNotInMandate.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::token::token_balance;
+
+/// Net asset value of the fund in main mint base units.
+///
+/// `valuation_accounts` must match `fund_valuation_accounts`: empty when the fund holds no other
+/// mints, otherwise the main mint oracle followed by (token account, oracle) for every other mint.
+pub fn fund_value(
+    fund_info: &AccountInfo<'_>,
+    fund: &Fund,
+    fund_main_token_account_info: &AccountInfo<'_>,
+    valuation_accounts: &[AccountInfo<'_>],
+) -> Result<u64, ProgramError> {
+    let (value, _) = fund_value_with_asset(
+        fund_info,
+        fund,
+        fund_main_token_account_info,
+        valuation_accounts,
+        &fund.main_mint,
+    )?;
+
+    Ok(value)
+}
+
+/// Like `fund_value`, also returning the value of the fund's `mint` holdings in main mint base
+/// units.
+pub fn fund_value_with_asset(
+    fund_info: &AccountInfo<'_>,
+    fund: &Fund,
+    fund_main_token_account_info: &AccountInfo<'_>,
+    valuation_accounts: &[AccountInfo<'_>],
+    mint: &Pubkey,
+) -> Result<(u64, u64), ProgramError> {
+    fund_main_token_account_info.has_seeds(
+        &[
+            TOKEN_ACCOUNT,
+            fund_info.key.as_ref(),
+            fund.main_mint.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
+
+    let mut value = token_balance(fund_main_token_account_info)?;
+    let mut asset_value = if *mint == fund.main_mint { value } else { 0 };
+
+    if fund.other_mints.is_empty() {
+        return Ok((value, asset_value));
+    }
+
+    let [main_oracle_info, other_accounts @ ..] = valuation_accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if other_accounts.len() != fund.other_mints.len() * 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    main_oracle_info.has_address(&fund.main_oracle)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let main_price =
+        PythPriceUpdate::load_price(main_oracle_info, &fund.main_feed_id, now, MAX_PRICE_AGE)?;
+
+    for ((other_mint, oracle, feed_id, decimals), accounts) in
+        fund.other_assets().zip(other_accounts.chunks_exact(2))
+    {
+        let [token_account_info, oracle_info] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        token_account_info.has_seeds(
+            &[TOKEN_ACCOUNT, fund_info.key.as_ref(), other_mint.as_ref()],
+            &yoko_program_api::ID,
+        )?;
+        oracle_info.has_address(oracle)?;
+
+        let balance = token_balance(token_account_info)?;
+        if balance == 0 {
+            continue;
+        }
+
+        let price = PythPriceUpdate::load_price(oracle_info, feed_id, now, MAX_PRICE_AGE)?;
+
+        let other_value =
+            convert_amount(balance, decimals, &price, fund.main_decimals, &main_price)?;
+        if other_mint == mint {
+            asset_value = other_value;
+        }
+
+        value = value
+            .checked_add(other_value)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    Ok((value, asset_value))
+}
@@ -0,0 +1,25 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+/// Fails if the protocol admin paused the protocol.
+pub fn check_protocol_not_paused(protocol_config_info: &AccountInfo<'_>) -> ProgramResult {
+    let protocol_config = protocol_config_info
+        .has_seeds(&[PROTOCOL_CONFIG], &yoko_program_api::ID)?
+        .as_account::<ProtocolConfig>(&yoko_program_api::ID)?;
+
+    if protocol_config.is_paused() {
+        return Err(YokoProgramError::ProtocolPaused.into());
+    }
+
+    Ok(())
+}
+
+/// Fails if either the protocol or the fund is paused. Claims and withdrawals don't check this, so
+/// that depositors can always exit.
+pub fn check_not_paused(protocol_config_info: &AccountInfo<'_>, fund: &Fund) -> ProgramResult {
+    if fund.is_paused() {
+        return Err(YokoProgramError::FundPaused.into());
+    }
+
+    check_protocol_not_paused(protocol_config_info)
+}
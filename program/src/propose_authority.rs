@@ -0,0 +1,26 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_propose_authority(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [fund_info, authority_info, new_authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?;
+
+    fund.pending_authority = *new_authority_info.key;
+
+    AuthorityProposed {
+        fund: *fund_info.key,
+        authority: *authority_info.key,
+        pending_authority: *new_authority_info.key,
+    }
+    .log();
+
+    Ok(())
+}
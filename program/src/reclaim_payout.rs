@@ -0,0 +1,89 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    pause::check_protocol_not_paused,
+    token::{close_token_account_signed, token_balance, transfer_checked_signed},
+};
+
+pub fn process_reclaim_payout(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = ReclaimPayout::try_from_bytes(data)?;
+    let counter = u64::from_le_bytes(args.counter);
+
+    let [fund_authority_info, fund_info, fund_token_account_info, payout_info, payout_token_account_info, rent_payer_info, mint_info, protocol_config_info, token_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    fund_authority_info.is_signer()?;
+
+    check_protocol_not_paused(protocol_config_info)?;
+
+    fund_info
+        .as_account::<Fund>(&yoko_program_api::ID)?
+        .assert(|fund| fund.authority == *fund_authority_info.key)?;
+
+    let payout = payout_info
+        .is_writable()?
+        .has_seeds(
+            &[PAYOUT, fund_info.key.as_ref(), &counter.to_le_bytes()],
+            &yoko_program_api::ID,
+        )?
+        .as_account::<Payout>(&yoko_program_api::ID)?;
+
+    if payout.claim_deadline == 0 || Clock::get()?.unix_timestamp <= payout.claim_deadline {
+        return Err(YokoProgramError::PayoutNotExpired.into());
+    }
+
+    rent_payer_info
+        .is_writable()?
+        .has_address(&payout.rent_payer)?;
+    mint_info.has_address(&payout.mint)?;
+    fund_token_account_info.is_writable()?.has_seeds(
+        &[
+            TOKEN_ACCOUNT,
+            fund_info.key.as_ref(),
+            mint_info.key.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
+    payout_token_account_info
+        .is_writable()?
+        .has_seeds(&[PAYOUT, payout_info.key.as_ref()], &yoko_program_api::ID)?;
+
+    let remaining = token_balance(payout_token_account_info)?;
+    if remaining > 0 {
+        transfer_checked_signed(
+            payout_info,
+            payout_token_account_info,
+            fund_token_account_info,
+            mint_info,
+            token_program,
+            remaining,
+            &[PAYOUT, fund_info.key.as_ref(), &counter.to_le_bytes()],
+        )?;
+    }
+
+    // Positions still behind the payout skip it when they next claim.
+    close_token_account_signed(
+        payout_token_account_info,
+        rent_payer_info,
+        mint_info,
+        payout_info,
+        token_program,
+        &[PAYOUT, fund_info.key.as_ref(), &counter.to_le_bytes()],
+    )?;
+
+    close_account(payout_info, rent_payer_info)?;
+
+    PayoutClosed {
+        fund: *fund_info.key,
+        payout: *payout_info.key,
+        counter,
+        reclaimed_amount: remaining,
+    }
+    .log();
+
+    Ok(())
+}
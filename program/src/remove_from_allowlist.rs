@@ -0,0 +1,32 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_remove_from_allowlist(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [fund_info, authority_info, allowlist_entry_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    fund_info
+        .as_account::<Fund>(&yoko_program_api::ID)?
+        .assert(|fund| fund.authority == *authority_info.key)?;
+
+    let depositor = allowlist_entry_info
+        .is_writable()?
+        .as_account::<AllowlistEntry>(&yoko_program_api::ID)?
+        .assert(|allowlist_entry| allowlist_entry.fund == *fund_info.key)?
+        .depositor;
+
+    // The depositor keeps an existing position, they just cannot open a new one.
+    close_account(allowlist_entry_info, authority_info)?;
+
+    AllowlistUpdated {
+        fund: *fund_info.key,
+        depositor,
+        allowed: 0,
+    }
+    .log();
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_request_redemption(accounts: &[AccountInfo<'_>]) -> ProgramResult {
+    let [position_info, position_authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    position_authority_info.is_signer()?;
+
+    let position = position_info
+        .is_writable()?
+        .as_account_mut::<Position>(&yoko_program_api::ID)?
+        .assert_mut(|position| position.authority == *position_authority_info.key)?
+        .assert_mut(|position| position.shares > 0)?;
+
+    position.redemption_requested_at = Clock::get()?.unix_timestamp;
+
+    RedemptionRequested {
+        fund: position.fund,
+        position: *position_info.key,
+        requested_at: position.redemption_requested_at,
+    }
+    .log();
+
+    Ok(())
+}
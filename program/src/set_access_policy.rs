@@ -0,0 +1,36 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_set_access_policy(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = SetAccessPolicy::try_from_bytes(data)?;
+    let access_mode =
+        AccessMode::try_from(args.access_mode).map_err(|_| ProgramError::InvalidArgument)?;
+
+    if access_mode == AccessMode::TokenGate && args.gate_mint == Pubkey::default() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let [fund_info, authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?;
+
+    // Existing positions are kept, the policy only applies to positions opened afterwards.
+    fund.access_mode = access_mode.into();
+    fund.merkle_root = args.merkle_root;
+    fund.gate_mint = args.gate_mint;
+
+    AccessPolicySet {
+        fund: *fund_info.key,
+        params: *args,
+    }
+    .log();
+
+    Ok(())
+}
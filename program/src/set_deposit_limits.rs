@@ -0,0 +1,34 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_set_deposit_limits(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = SetDepositLimits::try_from_bytes(data)?;
+    let deposit_cap = u64::from_le_bytes(args.deposit_cap);
+    let min_deposit = u64::from_le_bytes(args.min_deposit);
+    let max_position_deposit = u64::from_le_bytes(args.max_position_deposit);
+
+    let [fund_info, authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?;
+
+    // The limits only apply to new deposits, so a cap below `total_deposited` just closes the fund
+    // to new money.
+    fund.deposit_cap = deposit_cap;
+    fund.min_deposit = min_deposit;
+    fund.max_position_deposit = max_position_deposit;
+
+    DepositLimitsSet {
+        fund: *fund_info.key,
+        params: *args,
+    }
+    .log();
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_set_fund_paused(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = SetFundPaused::try_from_bytes(data)?;
+
+    let [fund_info, authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?;
+
+    fund.paused = (args.paused != 0).into();
+
+    FundPausedSet {
+        fund: *fund_info.key,
+        params: *args,
+    }
+    .log();
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_set_redemption_rules(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = SetRedemptionRules::try_from_bytes(data)?;
+    let lockup_period = i64::from_le_bytes(args.lockup_period);
+    let notice_period = i64::from_le_bytes(args.notice_period);
+
+    if lockup_period < 0 || notice_period < 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let [fund_info, authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?;
+
+    // Depositors joined under the current rules, so once there are shares they can only get
+    // shorter.
+    if fund.total_shares > 0
+        && (lockup_period > fund.lockup_period || notice_period > fund.notice_period)
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    fund.lockup_period = lockup_period;
+    fund.notice_period = notice_period;
+
+    RedemptionRulesSet {
+        fund: *fund_info.key,
+        params: *args,
+    }
+    .log();
+
+    Ok(())
+}
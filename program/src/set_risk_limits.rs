@@ -0,0 +1,54 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_set_risk_limits(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = SetRiskLimits::try_from_bytes(data)?;
+    let max_concentration_bps = u64::from_le_bytes(args.max_concentration_bps);
+    let max_trade_bps = u64::from_le_bytes(args.max_trade_bps);
+    let min_swap_interval = i64::from_le_bytes(args.min_swap_interval);
+
+    if max_concentration_bps > MAX_BPS || max_trade_bps > MAX_BPS || min_swap_interval < 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let [fund_info, authority_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    authority_info.is_signer()?;
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?
+        .assert_mut(|fund| fund.authority == *authority_info.key)?;
+
+    // Depositors joined under the current limits, so once there are shares they can only get stricter.
+    if fund.total_shares > 0
+        && (!is_stricter_bps(max_concentration_bps, fund.max_concentration_bps)
+            || !is_stricter_bps(max_trade_bps, fund.max_trade_bps)
+            || min_swap_interval < fund.min_swap_interval)
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    fund.max_concentration_bps = max_concentration_bps;
+    fund.max_trade_bps = max_trade_bps;
+    fund.min_swap_interval = min_swap_interval;
+
+    RiskLimitsSet {
+        fund: *fund_info.key,
+        params: *args,
+    }
+    .log();
+
+    Ok(())
+}
+
+/// Whether `new` is at least as strict as `current`, where zero means no limit.
+fn is_stricter_bps(new: u64, current: u64) -> bool {
+    match (new, current) {
+        (_, 0) => true,
+        (0, _) => false,
+        (new, current) => new <= current,
+    }
+}
@@ -0,0 +1,32 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_set_swap_program(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = SetSwapProgram::try_from_bytes(data)?;
+
+    if args.program == Pubkey::default() {
+        return Err(YokoProgramError::InvalidAccount.into());
+    }
+
+    let [protocol_config_info, admin_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    admin_info.is_signer()?;
+
+    let protocol_config = protocol_config_info
+        .is_writable()?
+        .has_seeds(&[PROTOCOL_CONFIG], &yoko_program_api::ID)?
+        .as_account_mut::<ProtocolConfig>(&yoko_program_api::ID)?
+        .assert_mut(|config| config.admin == *admin_info.key)?;
+
+    if args.allowed == 0 {
+        protocol_config.disallow_swap_program(&args.program);
+    } else if !protocol_config.allow_swap_program(args.program) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    SwapProgramSet { params: *args }.log();
+
+    Ok(())
+}
@@ -1,76 +1,166 @@
 use solana_program::{
     account_info::next_account_info,
     program::{invoke, invoke_signed},
-    program_pack::Pack,
 };
-use spl_token::{instruction::close_account, state::Account as SplTokenAccount};
+use spl_token_2022::instruction::close_account;
 use steel::*;
 use yoko_program_api::prelude::*;
 
-const JUPITER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+use crate::{
+    mandate::check_mandate,
+    nav::fund_value_with_asset,
+    token::{
+        check_token_program, token_account, token_balance, transfer_checked,
+        transfer_checked_signed,
+    },
+};
 
-pub fn process_swap<'a>(accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
-    let (in_amount, jupiter_route_cpi_data) = parse_instruction_data(data)?;
+pub fn process_swap(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let (in_amount, min_out_amount, jupiter_route_cpi_data) = parse_instruction_data(data)?;
 
     let accounts = SwapAccounts::new(accounts)?;
     let (fund, fund_pda_bump) = accounts.validate()?;
 
-    transfer_from_fund_to_user_source_ata(&accounts, in_amount)?;
-    let out_amount = execute_jupiter_swap(&accounts, jupiter_route_cpi_data, in_amount)?;
-    transfer_from_user_destination_ata_to_fund(&accounts, out_amount)?;
+    check_trade_limits(&accounts, fund, in_amount)?;
+    let swap_amount = transfer_from_fund_to_user_source_ata(&accounts, fund, in_amount)?;
+    let swapped_amount = execute_jupiter_swap(&accounts, jupiter_route_cpi_data, swap_amount)?;
+    // Transfer fees on either leg are part of the trade, so slippage and price are measured on
+    // what leaves and what arrives back in the fund.
+    let out_amount = transfer_from_user_destination_ata_to_fund(&accounts, swapped_amount)?;
+    if out_amount < min_out_amount {
+        return Err(YokoProgramError::SlippageExceeded.into());
+    }
+    check_oracle_price::<PythPriceUpdate>(&accounts, fund, in_amount, out_amount)?;
+    check_concentration(&accounts, fund)?;
     maybe_close_token_accounts(&accounts, fund, fund_pda_bump)?;
 
+    Swapped {
+        fund: *accounts.fund.key,
+        source_mint: *accounts.source_mint.key,
+        destination_mint: *accounts.destination_mint.key,
+        in_amount,
+        out_amount,
+    }
+    .log();
+
     Ok(())
 }
 
-fn parse_instruction_data(data: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
-    if data.len() < 16 {
+fn parse_instruction_data(data: &[u8]) -> Result<(u64, u64, &[u8]), ProgramError> {
+    if data.len() < 24 {
         return Err(ProgramError::InvalidInstructionData);
     }
 
-    let (in_amount, jupiter_route_cpi_data) = data.split_at(8);
+    let (in_amount, data) = data.split_at(8);
     let in_amount = in_amount
         .get(..8)
         .and_then(|slice| slice.try_into().ok())
         .map(u64::from_le_bytes)
         .ok_or(ProgramError::InvalidInstructionData)?;
 
-    Ok((in_amount, jupiter_route_cpi_data))
+    let (min_out_amount, jupiter_route_cpi_data) = data.split_at(8);
+    let min_out_amount = min_out_amount
+        .get(..8)
+        .and_then(|slice| slice.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((in_amount, min_out_amount, jupiter_route_cpi_data))
 }
 
-struct SwapAccounts<'a> {
-    fund_authority: &'a AccountInfo<'a>,
-    fund: &'a AccountInfo<'a>,
-    fund_destination_token_account: &'a AccountInfo<'a>,
-    fund_source_token_account: &'a AccountInfo<'a>,
-    jupiter_program: &'a AccountInfo<'a>,
-    jupiter_accounts: Vec<AccountInfo<'a>>,
+struct SwapAccounts<'a, 'info> {
+    fund_authority: &'a AccountInfo<'info>,
+    fund: &'a AccountInfo<'info>,
+    fund_destination_token_account: &'a AccountInfo<'info>,
+    fund_source_token_account: &'a AccountInfo<'info>,
+    protocol_config: &'a AccountInfo<'info>,
+    mandate: &'a AccountInfo<'info>,
+    source_oracle: &'a AccountInfo<'info>,
+    destination_oracle: &'a AccountInfo<'info>,
+    swap_program: &'a AccountInfo<'info>,
+    source_mint: &'a AccountInfo<'info>,
+    destination_mint: &'a AccountInfo<'info>,
+    source_token_program: &'a AccountInfo<'info>,
+    destination_token_program: &'a AccountInfo<'info>,
+    valuation_accounts: &'a [AccountInfo<'info>],
+    jupiter_accounts: Vec<AccountInfo<'info>>,
 }
 
-impl<'a> SwapAccounts<'a> {
-    fn new(accounts: &'a [AccountInfo<'a>]) -> Result<Self, ProgramError> {
+impl<'a, 'info> SwapAccounts<'a, 'info> {
+    fn new(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
         let account_info_iter = &mut accounts.iter();
 
+        let fund_authority = next_account_info(account_info_iter)?;
+        let fund = next_account_info(account_info_iter)?;
+        let fund_destination_token_account = next_account_info(account_info_iter)?;
+        let fund_source_token_account = next_account_info(account_info_iter)?;
+        let protocol_config = next_account_info(account_info_iter)?;
+        let mandate = next_account_info(account_info_iter)?;
+        let source_oracle = next_account_info(account_info_iter)?;
+        let destination_oracle = next_account_info(account_info_iter)?;
+        let swap_program = next_account_info(account_info_iter)?;
+        let source_mint = next_account_info(account_info_iter)?;
+        let destination_mint = next_account_info(account_info_iter)?;
+        let source_token_program = next_account_info(account_info_iter)?;
+        let destination_token_program = next_account_info(account_info_iter)?;
+
+        let remaining_accounts = &accounts[13..];
+        let valuation_accounts_len = Self::valuation_accounts_len(fund)?;
+        if remaining_accounts.len() < valuation_accounts_len {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (valuation_accounts, jupiter_accounts) =
+            remaining_accounts.split_at(valuation_accounts_len);
+
         Ok(Self {
-            fund_authority: next_account_info(account_info_iter)?,
-            fund: next_account_info(account_info_iter)?,
-            fund_destination_token_account: next_account_info(account_info_iter)?,
-            fund_source_token_account: next_account_info(account_info_iter)?,
-            jupiter_program: next_account_info(account_info_iter)?,
-            jupiter_accounts: accounts.iter().cloned().skip(5).collect(),
+            fund_authority,
+            fund,
+            fund_destination_token_account,
+            fund_source_token_account,
+            protocol_config,
+            mandate,
+            source_oracle,
+            destination_oracle,
+            swap_program,
+            source_mint,
+            destination_mint,
+            source_token_program,
+            destination_token_program,
+            valuation_accounts,
+            jupiter_accounts: jupiter_accounts.to_vec(),
         })
     }
 
+    /// Matches `swap_valuation_accounts`: the fund main token account and the fund valuation
+    /// accounts, only passed when the fund has a concentration limit.
+    fn valuation_accounts_len(fund_info: &AccountInfo<'_>) -> Result<usize, ProgramError> {
+        let fund = fund_info.as_account::<Fund>(&yoko_program_api::ID)?;
+
+        if fund.max_concentration_bps == 0 {
+            return Ok(0);
+        }
+
+        if fund.other_mints.is_empty() {
+            return Ok(1);
+        }
+
+        Ok(2 + fund.other_mints.len() * 2)
+    }
+
     fn validate(&self) -> Result<(&mut Fund, u8), ProgramError> {
         self.fund_authority.is_signer()?;
 
-        if *self.jupiter_program.key != JUPITER_PROGRAM_ID.parse::<Pubkey>().unwrap() {
-            return Err(YokoProgramError::InvalidAccount.into());
+        let protocol_config = self
+            .protocol_config
+            .has_seeds(&[PROTOCOL_CONFIG], &yoko_program_api::ID)?
+            .as_account::<ProtocolConfig>(&yoko_program_api::ID)?;
+
+        if protocol_config.is_paused() {
+            return Err(YokoProgramError::ProtocolPaused.into());
         }
 
-        let fund_pda = fund_pda(&self.fund_authority.key);
-        if *self.fund.key != fund_pda.0 {
-            return Err(YokoProgramError::InvalidAccount.into());
+        if !protocol_config.is_swap_program_allowed(self.swap_program.key) {
+            return Err(YokoProgramError::SwapProgramNotAllowed.into());
         }
 
         let fund = self
@@ -78,40 +168,81 @@ impl<'a> SwapAccounts<'a> {
             .as_account_mut::<Fund>(&yoko_program_api::ID)?
             .assert_mut(|fund| fund.authority == *self.fund_authority.key)?;
 
+        if fund.is_paused() {
+            return Err(YokoProgramError::FundPaused.into());
+        }
+
+        let fund_pda = fund_pda(&fund.creator, fund.id);
+        if *self.fund.key != fund_pda.0 {
+            return Err(YokoProgramError::InvalidAccount.into());
+        }
+
+        // Both sides must be the fund token accounts of mints the fund tracks, otherwise a swap
+        // could move value into an account the net asset value does not count.
+        for (token_account_info, mint_info) in [
+            (self.fund_source_token_account, self.source_mint),
+            (self.fund_destination_token_account, self.destination_mint),
+        ] {
+            if *mint_info.key != fund.main_mint && !fund.other_mints.contains(mint_info.key) {
+                return Err(YokoProgramError::InvalidAccount.into());
+            }
+
+            token_account_info.has_seeds(
+                &[
+                    TOKEN_ACCOUNT,
+                    self.fund.key.as_ref(),
+                    mint_info.key.as_ref(),
+                ],
+                &yoko_program_api::ID,
+            )?;
+        }
+
+        let fund_source_token_account_data = token_account(self.fund_source_token_account)?;
         let fund_destination_token_account_data =
-            SplTokenAccount::unpack(&self.fund_destination_token_account.data.borrow())?;
+            token_account(self.fund_destination_token_account)?;
 
         if fund_destination_token_account_data.owner != *self.fund.key {
             return Err(YokoProgramError::InvalidAccount.into());
         }
 
-        Ok((fund, fund_pda.1))
-    }
+        if fund_source_token_account_data.mint != *self.source_mint.key
+            || fund_destination_token_account_data.mint != *self.destination_mint.key
+        {
+            return Err(YokoProgramError::InvalidAccount.into());
+        }
+
+        check_token_program(self.source_mint, self.source_token_program)?;
+        check_token_program(self.destination_mint, self.destination_token_program)?;
+
+        check_mandate(self.fund, fund, self.mandate, self.destination_mint.key)?;
 
-    fn token_program(&self) -> &AccountInfo<'a> {
-        &self.jupiter_accounts[0]
+        Ok((fund, fund_pda.1))
     }
 
-    fn user_source_ata(&self) -> &AccountInfo<'a> {
+    fn user_source_ata(&self) -> &AccountInfo<'info> {
         &self.jupiter_accounts[2]
     }
 
-    fn user_destination_ata(&self) -> &AccountInfo<'a> {
+    fn user_destination_ata(&self) -> &AccountInfo<'info> {
         &self.jupiter_accounts[3]
     }
 }
 
-fn transfer_from_fund_to_user_source_ata(accounts: &SwapAccounts, amount: u64) -> ProgramResult {
-    transfer_signed(
+/// Returns the amount the user source token account received, which is what gets swapped.
+fn transfer_from_fund_to_user_source_ata(
+    accounts: &SwapAccounts,
+    fund: &Fund,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    transfer_checked_signed(
         accounts.fund,
         accounts.fund_source_token_account,
         accounts.user_source_ata(),
-        accounts.token_program(),
+        accounts.source_mint,
+        accounts.source_token_program,
         amount,
-        &[FUND, accounts.fund_authority.key.as_ref()],
-    )?;
-
-    Ok(())
+        &[FUND, fund.creator.as_ref(), &fund.id.to_le_bytes()],
+    )
 }
 
 fn execute_jupiter_swap(
@@ -119,11 +250,9 @@ fn execute_jupiter_swap(
     jupiter_route_cpi_data: &[u8],
     amount: u64,
 ) -> Result<u64, ProgramError> {
-    let user_source_ata_amount_before_swap =
-        SplTokenAccount::unpack(&accounts.user_source_ata().data.borrow())?.amount;
+    let user_source_ata_amount_before_swap = token_balance(accounts.user_source_ata())?;
 
-    let user_destination_ata_amount_before_swap =
-        SplTokenAccount::unpack(&accounts.user_destination_ata().data.borrow())?.amount;
+    let user_destination_ata_amount_before_swap = token_balance(accounts.user_destination_ata())?;
 
     let jup_accounts_metas: Vec<AccountMeta> = accounts
         .jupiter_accounts
@@ -137,18 +266,16 @@ fn execute_jupiter_swap(
 
     invoke(
         &Instruction {
-            program_id: *accounts.jupiter_program.key,
+            program_id: *accounts.swap_program.key,
             accounts: jup_accounts_metas,
             data: jupiter_route_cpi_data.to_vec(),
         },
         accounts.jupiter_accounts.as_slice(),
     )?;
 
-    let user_source_ata_amount_after_swap =
-        SplTokenAccount::unpack(&accounts.user_source_ata().data.borrow())?.amount;
+    let user_source_ata_amount_after_swap = token_balance(accounts.user_source_ata())?;
 
-    let user_destination_ata_amount_after_swap =
-        SplTokenAccount::unpack(&accounts.user_destination_ata().data.borrow())?.amount;
+    let user_destination_ata_amount_after_swap = token_balance(accounts.user_destination_ata())?;
 
     let source_difference = user_source_ata_amount_before_swap
         .checked_sub(user_source_ata_amount_after_swap)
@@ -165,15 +292,116 @@ fn execute_jupiter_swap(
     Ok(destination_difference)
 }
 
+/// Enforces the per-trade size limit and the minimum interval between swaps.
+fn check_trade_limits(accounts: &SwapAccounts, fund: &mut Fund, in_amount: u64) -> ProgramResult {
+    if fund.max_trade_bps > 0 {
+        let source_balance = token_balance(accounts.fund_source_token_account)?;
+
+        if (in_amount as u128) * (MAX_BPS as u128)
+            > (source_balance as u128) * (fund.max_trade_bps as u128)
+        {
+            return Err(YokoProgramError::TradeSizeLimitExceeded.into());
+        }
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if fund.min_swap_interval > 0 && now.saturating_sub(fund.last_swap_at) < fund.min_swap_interval
+    {
+        return Err(YokoProgramError::SwapTooSoon.into());
+    }
+    fund.last_swap_at = now;
+
+    Ok(())
+}
+
+/// Fails when the destination mint makes up more of the fund value than the concentration limit.
+fn check_concentration(accounts: &SwapAccounts, fund: &Fund) -> ProgramResult {
+    if fund.max_concentration_bps == 0 {
+        return Ok(());
+    }
+
+    let destination_mint = *accounts.destination_mint.key;
+
+    if destination_mint == fund.main_mint {
+        return Ok(());
+    }
+
+    let [fund_main_token_account_info, valuation_accounts @ ..] = accounts.valuation_accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (value, destination_value) = fund_value_with_asset(
+        accounts.fund,
+        fund,
+        fund_main_token_account_info,
+        valuation_accounts,
+        &destination_mint,
+    )?;
+
+    if (destination_value as u128) * (MAX_BPS as u128)
+        > (value as u128) * (fund.max_concentration_bps as u128)
+    {
+        return Err(YokoProgramError::ConcentrationLimitExceeded.into());
+    }
+
+    Ok(())
+}
+
+/// Compares the swap against oracle prices when the fund has a deviation tolerance.
+fn check_oracle_price<F: PriceFeed>(
+    accounts: &SwapAccounts,
+    fund: &Fund,
+    in_amount: u64,
+    out_amount: u64,
+) -> ProgramResult {
+    if fund.max_swap_deviation_bps == 0 {
+        return Ok(());
+    }
+
+    let (source_oracle, source_feed_id, source_decimals) = fund
+        .asset(accounts.source_mint.key)
+        .ok_or(YokoProgramError::InvalidAccount)?;
+    let (destination_oracle, destination_feed_id, destination_decimals) = fund
+        .asset(accounts.destination_mint.key)
+        .ok_or(YokoProgramError::InvalidAccount)?;
+
+    accounts.source_oracle.has_address(&source_oracle)?;
+    accounts
+        .destination_oracle
+        .has_address(&destination_oracle)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let source_price = F::load_price(accounts.source_oracle, &source_feed_id, now, MAX_PRICE_AGE)?;
+    let destination_price = F::load_price(
+        accounts.destination_oracle,
+        &destination_feed_id,
+        now,
+        MAX_PRICE_AGE,
+    )?;
+
+    check_swap_price(
+        in_amount,
+        source_decimals,
+        &source_price,
+        out_amount,
+        destination_decimals,
+        &destination_price,
+        fund.max_swap_deviation_bps,
+    )
+}
+
+/// Returns the amount the fund destination token account received.
 fn transfer_from_user_destination_ata_to_fund(
     accounts: &SwapAccounts,
     amount: u64,
-) -> ProgramResult {
-    transfer(
+) -> Result<u64, ProgramError> {
+    transfer_checked(
         accounts.fund_authority,
         accounts.user_destination_ata(),
         accounts.fund_destination_token_account,
-        accounts.token_program(),
+        accounts.destination_mint,
+        accounts.destination_token_program,
         amount,
     )
 }
@@ -183,57 +411,29 @@ fn maybe_close_token_accounts(
     fund: &mut Fund,
     fund_pda_bump: u8,
 ) -> ProgramResult {
-    let user_source_ata_amount =
-        SplTokenAccount::unpack(&accounts.user_source_ata().data.borrow())?.amount;
-
-    if user_source_ata_amount <= 0 {
-        invoke(
-            &close_account(
-                accounts.token_program().key,
-                accounts.user_source_ata().key,
-                accounts.fund_authority.key,
-                accounts.fund_authority.key,
-                &[accounts.fund_authority.key],
-            )?,
-            &[
-                accounts.user_source_ata().clone(),
-                accounts.fund_authority.clone(),
-                accounts.fund_authority.clone(),
-                accounts.token_program().clone(),
-            ],
+    // Token-2022 refuses to close accounts still holding withheld transfer fees.
+    if is_token_account_closable(&accounts.user_source_ata().data.borrow())? {
+        close_user_token_account(
+            accounts,
+            accounts.user_source_ata(),
+            accounts.source_token_program,
         )?;
     }
 
-    let user_destination_ata_amount =
-        SplTokenAccount::unpack(&accounts.user_destination_ata().data.borrow())?.amount;
-
-    if user_destination_ata_amount <= 0 {
-        invoke(
-            &close_account(
-                accounts.token_program().key,
-                accounts.user_destination_ata().key,
-                accounts.fund_authority.key,
-                accounts.fund_authority.key,
-                &[accounts.fund_authority.key],
-            )?,
-            &[
-                accounts.user_destination_ata().clone(),
-                accounts.fund_authority.clone(),
-                accounts.fund_authority.clone(),
-                accounts.token_program().clone(),
-            ],
+    if is_token_account_closable(&accounts.user_destination_ata().data.borrow())? {
+        close_user_token_account(
+            accounts,
+            accounts.user_destination_ata(),
+            accounts.destination_token_program,
         )?;
     }
 
-    let fund_source_token_account_data =
-        SplTokenAccount::unpack(&accounts.fund_source_token_account.data.borrow())?;
-
-    if fund_source_token_account_data.mint != fund.main_mint
-        && fund_source_token_account_data.amount <= 0
+    if *accounts.source_mint.key != fund.main_mint
+        && is_token_account_closable(&accounts.fund_source_token_account.data.borrow())?
     {
         invoke_signed(
             &close_account(
-                accounts.token_program().key,
+                accounts.source_token_program.key,
                 accounts.fund_source_token_account.key,
                 accounts.fund_authority.key,
                 accounts.fund.key,
@@ -243,14 +443,17 @@ fn maybe_close_token_accounts(
                 accounts.fund_source_token_account.clone(),
                 accounts.fund_authority.clone(),
                 accounts.fund.clone(),
-                accounts.token_program().clone(),
+                accounts.source_token_program.clone(),
             ],
-            &[&[FUND, accounts.fund_authority.key.as_ref(), &[fund_pda_bump]]],
+            &[&[
+                FUND,
+                fund.creator.as_ref(),
+                &fund.id.to_le_bytes(),
+                &[fund_pda_bump],
+            ]],
         )?;
 
-        let removed = fund
-            .other_mints
-            .remove(&fund_source_token_account_data.mint);
+        let removed = fund.remove_other_mint(accounts.source_mint.key);
         if !removed {
             return Err(YokoProgramError::ErrorRemovingOtherMint.into());
         }
@@ -258,3 +461,25 @@ fn maybe_close_token_accounts(
 
     Ok(())
 }
+
+fn close_user_token_account<'info>(
+    accounts: &SwapAccounts<'_, 'info>,
+    token_account_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> ProgramResult {
+    invoke(
+        &close_account(
+            token_program.key,
+            token_account_info.key,
+            accounts.fund_authority.key,
+            accounts.fund_authority.key,
+            &[accounts.fund_authority.key],
+        )?,
+        &[
+            token_account_info.clone(),
+            accounts.fund_authority.clone(),
+            accounts.fund_authority.clone(),
+            token_program.clone(),
+        ],
+    )
+}
@@ -0,0 +1,217 @@
+use solana_program::program::{invoke, invoke_signed};
+use spl_associated_token_account::tools::account::create_pda_account;
+use spl_token_2022::{
+    extension::transfer_fee::instruction::harvest_withheld_tokens_to_mint,
+    instruction::{
+        close_account, initialize_account3, transfer_checked as transfer_checked_instruction,
+    },
+    state::Account,
+};
+use steel::*;
+use sysvar::rent::Rent;
+use yoko_program_api::prelude::*;
+
+/// Unpacks a token account of either token program.
+pub fn token_account(token_account_info: &AccountInfo<'_>) -> Result<Account, ProgramError> {
+    if !is_token_program(token_account_info.owner) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    unpack_token_account(&token_account_info.data.borrow())
+}
+
+pub fn token_balance(token_account_info: &AccountInfo<'_>) -> Result<u64, ProgramError> {
+    Ok(token_account(token_account_info)?.amount)
+}
+
+/// Fails unless `token_program` is the token program that owns `mint_info`.
+pub fn check_token_program(
+    mint_info: &AccountInfo<'_>,
+    token_program: &AccountInfo<'_>,
+) -> ProgramResult {
+    if !is_token_program(token_program.key) || mint_info.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    Ok(())
+}
+
+/// Transfers `amount` with `transfer_checked` and returns how much `to_info` received, which is
+/// less than `amount` when the mint charges a transfer fee.
+pub fn transfer_checked<'info>(
+    authority_info: &AccountInfo<'info>,
+    from_info: &AccountInfo<'info>,
+    to_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    invoke_transfer_checked(
+        authority_info,
+        from_info,
+        to_info,
+        mint_info,
+        token_program,
+        amount,
+        &[],
+    )
+}
+
+/// Like `transfer_checked`, signing for the program-derived `authority_info` with `seeds`.
+pub fn transfer_checked_signed<'info>(
+    authority_info: &AccountInfo<'info>,
+    from_info: &AccountInfo<'info>,
+    to_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+    seeds: &[&[u8]],
+) -> Result<u64, ProgramError> {
+    let bump = [Pubkey::find_program_address(seeds, authority_info.owner).1];
+    let signer_seeds = [seeds, &[&bump[..]]].concat();
+
+    invoke_transfer_checked(
+        authority_info,
+        from_info,
+        to_info,
+        mint_info,
+        token_program,
+        amount,
+        &[&signer_seeds],
+    )
+}
+
+fn invoke_transfer_checked<'info>(
+    authority_info: &AccountInfo<'info>,
+    from_info: &AccountInfo<'info>,
+    to_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64, ProgramError> {
+    check_token_program(mint_info, token_program)?;
+
+    let decimals = unpack_mint(&mint_info.data.borrow())?.decimals;
+    let balance_before = token_balance(to_info)?;
+
+    solana_program::program::invoke_signed(
+        &transfer_checked_instruction(
+            token_program.key,
+            from_info.key,
+            mint_info.key,
+            to_info.key,
+            authority_info.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            from_info.clone(),
+            mint_info.clone(),
+            to_info.clone(),
+            authority_info.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    token_balance(to_info)?
+        .checked_sub(balance_before)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// Creates the token account at the program-derived address of `seeds`, sized for the extensions
+/// the mint requires, and initializes it for `owner`.
+pub fn create_token_account<'info>(
+    payer_info: &AccountInfo<'info>,
+    token_account_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    owner: &Pubkey,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    check_token_program(mint_info, token_program)?;
+
+    let (address, bump) = Pubkey::find_program_address(seeds, &yoko_program_api::ID);
+    if *token_account_info.key != address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let bump = [bump];
+    let signer_seeds = [seeds, &[&bump[..]]].concat();
+
+    create_pda_account(
+        payer_info,
+        &Rent::get()?,
+        token_account_len(&mint_info.data.borrow())?,
+        token_program.key,
+        system_program,
+        token_account_info,
+        &signer_seeds,
+    )?;
+
+    invoke(
+        &initialize_account3(
+            token_program.key,
+            token_account_info.key,
+            mint_info.key,
+            owner,
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            token_program.clone(),
+        ],
+    )
+}
+
+/// Closes the empty token account owned by the program-derived `authority_info` of `seeds`,
+/// sending its rent to `destination_info`. Transfer fees withheld in the account are harvested to
+/// the mint first, since Token-2022 refuses to close an account still holding them.
+pub fn close_token_account_signed<'info>(
+    token_account_info: &AccountInfo<'info>,
+    destination_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    authority_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    check_token_program(mint_info, token_program)?;
+
+    if withheld_amount(&token_account_info.data.borrow())? > 0 {
+        invoke(
+            &harvest_withheld_tokens_to_mint(
+                token_program.key,
+                mint_info.key,
+                &[token_account_info.key],
+            )?,
+            &[
+                mint_info.clone(),
+                token_account_info.clone(),
+                token_program.clone(),
+            ],
+        )?;
+    }
+
+    let bump = [Pubkey::find_program_address(seeds, authority_info.owner).1];
+    let signer_seeds = [seeds, &[&bump[..]]].concat();
+
+    invoke_signed(
+        &close_account(
+            token_program.key,
+            token_account_info.key,
+            destination_info.key,
+            authority_info.key,
+            &[],
+        )?,
+        &[
+            token_account_info.clone(),
+            destination_info.clone(),
+            authority_info.clone(),
+            token_program.clone(),
+        ],
+        &[&signer_seeds],
+    )
+}
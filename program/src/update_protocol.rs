@@ -0,0 +1,43 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+pub fn process_update_protocol(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = UpdateProtocol::try_from_bytes(data)?;
+    let protocol_fee_bps = u64::from_le_bytes(args.protocol_fee_bps);
+
+    if protocol_fee_bps > MAX_PROTOCOL_FEE_BPS {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
+    if args.admin == Pubkey::default() {
+        return Err(YokoProgramError::InvalidAccount.into());
+    }
+
+    let [protocol_config_info, admin_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    admin_info.is_signer()?;
+
+    let protocol_config = protocol_config_info
+        .is_writable()?
+        .has_seeds(&[PROTOCOL_CONFIG], &yoko_program_api::ID)?
+        .as_account_mut::<ProtocolConfig>(&yoko_program_api::ID)?
+        .assert_mut(|config| config.admin == *admin_info.key)?;
+
+    protocol_config.admin = args.admin;
+    protocol_config.fee_recipient = args.fee_recipient;
+    protocol_config.protocol_fee_bps = protocol_fee_bps;
+    protocol_config.paused = args.paused;
+
+    ProtocolUpdated {
+        admin: protocol_config.admin,
+        fee_recipient: protocol_config.fee_recipient,
+        protocol_fee_bps: protocol_config.protocol_fee_bps,
+        paused: protocol_config.paused,
+        _padding: [0; 7],
+    }
+    .log();
+
+    Ok(())
+}
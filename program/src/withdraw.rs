@@ -0,0 +1,199 @@
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::{
+    accrue_management_fee::{accrue_management_fee, ManagementFeeAccounts},
+    nav::fund_value,
+    token::{token_balance, transfer_checked_signed},
+};
+
+pub fn process_withdraw(accounts: &[AccountInfo<'_>], data: &[u8]) -> ProgramResult {
+    let args = Withdraw::try_from_bytes(data)?;
+    let shares = u64::from_le_bytes(args.shares);
+
+    let [position_info, position_authority_info, fund_info, fund_main_token_account_info, depositor_main_token_account_info, main_mint_info, token_program, fund_authority_token_account_info, remaining_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    position_authority_info.is_signer()?;
+
+    if shares == 0 {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
+    let fund = fund_info
+        .is_writable()?
+        .as_account_mut::<Fund>(&yoko_program_api::ID)?;
+    main_mint_info.has_address(&fund.main_mint)?;
+
+    let position = position_info
+        .as_account_mut::<Position>(&yoko_program_api::ID)?
+        .assert_mut(|position| position.authority == *position_authority_info.key)?
+        .assert_mut(|position| position.fund == *fund_info.key)?
+        .assert_mut(|position| position.payouts_counter == fund.payouts_counter)?;
+
+    if shares > position.shares {
+        return Err(YokoProgramError::InvalidAmount.into());
+    }
+
+    check_redemption_rules(fund, position)?;
+
+    // The burned shares are also worth a part of every other mint, so each is withdrawn in kind
+    // as a (fund token account, depositor token account, mint, token program) group. The
+    // valuation accounts follow.
+    if remaining_accounts.len() < fund.other_mints.len() * 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (other_token_accounts, valuation_accounts) =
+        remaining_accounts.split_at(fund.other_mints.len() * 4);
+
+    fund_main_token_account_info.has_seeds(
+        &[
+            TOKEN_ACCOUNT,
+            fund_info.key.as_ref(),
+            fund.main_mint.as_ref(),
+        ],
+        &yoko_program_api::ID,
+    )?;
+
+    // The management fee owed until now is taken before the shares are paid out. Funds without
+    // one skip the valuation, so that stale oracles never hold up withdrawals.
+    if fund.management_fee_bps != 0 {
+        let value = fund_value(
+            fund_info,
+            fund,
+            fund_main_token_account_info,
+            valuation_accounts,
+        )?;
+
+        accrue_management_fee(
+            &ManagementFeeAccounts {
+                fund: fund_info,
+                fund_main_token_account: fund_main_token_account_info,
+                fund_authority_token_account: fund_authority_token_account_info,
+                main_mint: main_mint_info,
+                token_program,
+            },
+            fund,
+            value,
+        )?;
+    }
+
+    let main_amount = transfer_pro_rata(
+        fund_info,
+        fund,
+        fund_main_token_account_info,
+        depositor_main_token_account_info,
+        main_mint_info,
+        token_program,
+        shares,
+    )?;
+
+    for (mint, token_accounts) in fund
+        .other_mints
+        .iter()
+        .zip(other_token_accounts.chunks_exact(4))
+    {
+        let [fund_token_account_info, depositor_token_account_info, mint_info, token_program] =
+            token_accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        mint_info.has_address(mint)?;
+
+        fund_token_account_info.has_seeds(
+            &[TOKEN_ACCOUNT, fund_info.key.as_ref(), mint.as_ref()],
+            &yoko_program_api::ID,
+        )?;
+
+        let amount = transfer_pro_rata(
+            fund_info,
+            fund,
+            fund_token_account_info,
+            depositor_token_account_info,
+            mint_info,
+            token_program,
+            shares,
+        )?;
+
+        WithdrawnInKind {
+            fund: *fund_info.key,
+            position: *position_info.key,
+            mint: *mint,
+            amount,
+        }
+        .log();
+    }
+
+    position.deposited -= pro_rata(position.deposited, shares, position.shares)?;
+    fund.total_deposited -= pro_rata(fund.total_deposited, shares, fund.total_shares)?;
+
+    position.shares -= shares;
+    position.redemption_requested_at = 0;
+
+    fund.total_shares = fund
+        .total_shares
+        .checked_sub(shares)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Withdrawn {
+        fund: *fund_info.key,
+        position: *position_info.key,
+        shares,
+        total_shares: fund.total_shares,
+        main_amount,
+        in_kind_mints: fund.other_mints.len() as u64,
+    }
+    .log();
+
+    Ok(())
+}
+
+fn check_redemption_rules(fund: &Fund, position: &Position) -> ProgramResult {
+    let now = Clock::get()?.unix_timestamp;
+
+    if now < position.unlocks_at(fund) {
+        return Err(YokoProgramError::PositionLocked.into());
+    }
+
+    match position.withdrawable_at(fund) {
+        None => Err(YokoProgramError::RedemptionNotRequested.into()),
+        Some(withdrawable_at) if now < withdrawable_at => {
+            Err(YokoProgramError::NoticePeriodNotElapsed.into())
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Transfers `shares / fund.total_shares` of the fund token account balance to the depositor and
+/// returns the amount the depositor received.
+fn transfer_pro_rata<'info>(
+    fund_info: &AccountInfo<'info>,
+    fund: &Fund,
+    fund_token_account_info: &AccountInfo<'info>,
+    depositor_token_account_info: &AccountInfo<'info>,
+    mint_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    shares: u64,
+) -> Result<u64, ProgramError> {
+    let balance = token_balance(fund_token_account_info)?;
+
+    let amount = pro_rata(balance, shares, fund.total_shares)?;
+
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    transfer_checked_signed(
+        fund_info,
+        fund_token_account_info,
+        depositor_token_account_info,
+        mint_info,
+        token_program,
+        amount,
+        &[FUND, fund.creator.as_ref(), &fund.id.to_le_bytes()],
+    )
+}
@@ -0,0 +1,81 @@
+use solana_program::{
+    program::{invoke, invoke_signed},
+    system_instruction,
+};
+use spl_token_2022::instruction::{close_account, sync_native};
+use steel::*;
+use yoko_program_api::prelude::*;
+
+use crate::token::create_token_account;
+
+/// Creates the temporary WSOL account of the position, owned by the position, and wraps `amount`
+/// lamports of `payer_info` in it.
+pub fn create_wsol_account<'info>(
+    payer_info: &AccountInfo<'info>,
+    wsol_account_info: &AccountInfo<'info>,
+    position_info: &AccountInfo<'info>,
+    native_mint_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> ProgramResult {
+    native_mint_info.has_address(&spl_token::native_mint::ID)?;
+
+    create_token_account(
+        payer_info,
+        wsol_account_info,
+        native_mint_info,
+        position_info.key,
+        token_program,
+        system_program,
+        &[WSOL, position_info.key.as_ref()],
+    )?;
+
+    if amount == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(payer_info.key, wsol_account_info.key, amount),
+        &[
+            payer_info.clone(),
+            wsol_account_info.clone(),
+            system_program.clone(),
+        ],
+    )?;
+
+    invoke(
+        &sync_native(token_program.key, wsol_account_info.key)?,
+        &[wsol_account_info.clone(), token_program.clone()],
+    )
+}
+
+/// Closes the temporary WSOL account, sending its wrapped lamports and its rent to
+/// `recipient_info`. `position_seeds` are the seeds of the position, without the bump.
+pub fn close_wsol_account<'info>(
+    wsol_account_info: &AccountInfo<'info>,
+    position_info: &AccountInfo<'info>,
+    recipient_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    position_seeds: &[&[u8]],
+) -> ProgramResult {
+    let bump = [Pubkey::find_program_address(position_seeds, &yoko_program_api::ID).1];
+    let signer_seeds = [position_seeds, &[&bump[..]]].concat();
+
+    invoke_signed(
+        &close_account(
+            token_program.key,
+            wsol_account_info.key,
+            recipient_info.key,
+            position_info.key,
+            &[],
+        )?,
+        &[
+            wsol_account_info.clone(),
+            recipient_info.clone(),
+            position_info.clone(),
+            token_program.clone(),
+        ],
+        &[&signer_seeds],
+    )
+}
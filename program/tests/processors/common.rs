@@ -0,0 +1,489 @@
+//! Runs the processors in a local bank with the SPL token programs. The protocol config and the
+//! main mint oracle are written straight into the bank, everything else goes through instructions.
+
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_token_2022::{
+    extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+    state::Mint,
+};
+use steel::*;
+use yoko_program_api::{
+    prelude::*,
+    test_utils::{account_data, price_update},
+};
+
+pub const MAIN_FEED_ID: [u8; 32] = [7; 32];
+pub const MAIN_DECIMALS: u8 = 6;
+
+/// Fund and protocol settings of a test. Defaults to a classic SPL Token main mint and no fees.
+pub struct TestConfig {
+    pub token_program: Pubkey,
+    pub transfer_fee_bps: Option<u16>,
+    pub protocol_fee_bps: u64,
+    pub fee_mode: FeeMode,
+    pub authority_fee_bps: u64,
+    pub hurdle_bps: u64,
+    pub management_fee_bps: u64,
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        Self {
+            token_program: spl_token::ID,
+            transfer_fee_bps: None,
+            protocol_fee_bps: 0,
+            fee_mode: FeeMode::Payout,
+            authority_fee_bps: 0,
+            hurdle_bps: 0,
+            management_fee_bps: 0,
+        }
+    }
+}
+
+/// A fund holding only its main mint, created by the payer of `context`, which is also the mint
+/// authority.
+pub struct TestFund {
+    pub context: ProgramTestContext,
+    pub fund: Pubkey,
+    pub main_mint: Pubkey,
+    pub token_program: Pubkey,
+    pub fund_main_token_account: Pubkey,
+    /// Main mint token account of the fund authority, receiving its fees.
+    pub authority_token_account: Pubkey,
+    /// Main mint token account of the protocol fee recipient.
+    pub fee_token_account: Pubkey,
+}
+
+impl TestFund {
+    pub async fn start(config: TestConfig) -> Self {
+        let mut program_test = ProgramTest::new(
+            "yoko_program",
+            yoko_program_api::ID,
+            processor!(yoko_program_program::process_instruction),
+        );
+
+        let fee_recipient = Pubkey::new_unique();
+        add_account(
+            &mut program_test,
+            protocol_config_pda().0,
+            yoko_program_api::ID,
+            account_data(&ProtocolConfig {
+                admin: INITIAL_PROTOCOL_ADMIN,
+                fee_recipient,
+                protocol_fee_bps: config.protocol_fee_bps,
+                paused: 0,
+                _padding: [0; 7],
+                swap_programs: [Pubkey::default(); MAX_SWAP_PROGRAMS],
+            }),
+        );
+
+        let main_oracle = Pubkey::new_unique();
+        add_account(
+            &mut program_test,
+            main_oracle,
+            PythPriceUpdate::owner(),
+            price_update(&[1], &MAIN_FEED_ID, 1_000_000, 0),
+        );
+
+        let context = program_test.start_with_context().await;
+        let authority = context.payer.pubkey();
+        let fund = fund_pda(&authority, 0).0;
+
+        let mut test_fund = Self {
+            context,
+            fund,
+            main_mint: Pubkey::default(),
+            token_program: config.token_program,
+            fund_main_token_account: Pubkey::default(),
+            authority_token_account: Pubkey::default(),
+            fee_token_account: Pubkey::default(),
+        };
+
+        test_fund.main_mint = test_fund.create_mint(config.transfer_fee_bps).await;
+        test_fund.fund_main_token_account = fund_token_account_pda(&fund, &test_fund.main_mint).0;
+        test_fund.authority_token_account = test_fund.create_token_account(&authority).await;
+        test_fund.fee_token_account = test_fund.create_token_account(&fee_recipient).await;
+
+        test_fund
+            .process(
+                &[create_fund(
+                    fund,
+                    authority,
+                    test_fund.main_mint,
+                    test_fund.fund_main_token_account,
+                    main_oracle,
+                    config.token_program,
+                    0,
+                    config.authority_fee_bps,
+                    config.fee_mode,
+                    config.hurdle_bps,
+                    config.management_fee_bps,
+                    0,
+                    0,
+                    0,
+                    0,
+                )],
+                &[],
+            )
+            .await
+            .unwrap();
+
+        test_fund
+    }
+
+    /// Sends `instructions` in one transaction paid by the fund authority and signed by `signers`.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self
+            .context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    pub async fn state<T: Discriminator + Pod>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap();
+
+        *T::try_from_bytes(&account.data).unwrap()
+    }
+
+    /// Moves the clock to `unix_timestamp`.
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+
+        self.context.set_sysvar(&Clock {
+            unix_timestamp,
+            ..clock
+        });
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+    }
+
+    pub async fn fund_state(&mut self) -> Fund {
+        let fund = self.fund;
+        self.state(&fund).await
+    }
+
+    pub async fn balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .unwrap();
+
+        unpack_token_account(&account.data).unwrap().amount
+    }
+
+    async fn create_mint(&mut self, transfer_fee_bps: Option<u16>) -> Pubkey {
+        let mint = Keypair::new();
+        let authority = self.authority();
+
+        let extensions = transfer_fee_bps
+            .map(|_| vec![ExtensionType::TransferFeeConfig])
+            .unwrap_or_default();
+        let len = if extensions.is_empty() {
+            Mint::LEN
+        } else {
+            ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap()
+        };
+
+        let mut instructions = vec![system_instruction::create_account(
+            &authority,
+            &mint.pubkey(),
+            Rent::default().minimum_balance(len),
+            len as u64,
+            &self.token_program,
+        )];
+        if let Some(transfer_fee_bps) = transfer_fee_bps {
+            instructions.push(
+                initialize_transfer_fee_config(
+                    &self.token_program,
+                    &mint.pubkey(),
+                    None,
+                    None,
+                    transfer_fee_bps,
+                    u64::MAX,
+                )
+                .unwrap(),
+            );
+        }
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &self.token_program,
+                &mint.pubkey(),
+                &authority,
+                None,
+                MAIN_DECIMALS,
+            )
+            .unwrap(),
+        );
+
+        self.process(&instructions, &[&mint]).await.unwrap();
+
+        mint.pubkey()
+    }
+
+    /// Creates a main mint token account owned by `owner`.
+    pub async fn create_token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let token_account = Keypair::new();
+        let mint_data = self
+            .context
+            .banks_client
+            .get_account(self.main_mint)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let len = token_account_len(&mint_data).unwrap();
+
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &self.authority(),
+                    &token_account.pubkey(),
+                    Rent::default().minimum_balance(len),
+                    len as u64,
+                    &self.token_program,
+                ),
+                spl_token_2022::instruction::initialize_account3(
+                    &self.token_program,
+                    &token_account.pubkey(),
+                    &self.main_mint,
+                    owner,
+                )
+                .unwrap(),
+            ],
+            &[&token_account],
+        )
+        .await
+        .unwrap();
+
+        token_account.pubkey()
+    }
+
+    /// Mints `amount` of the main mint into `token_account`.
+    pub async fn mint_to(&mut self, token_account: &Pubkey, amount: u64) {
+        let authority = self.authority();
+
+        self.process(
+            &[spl_token_2022::instruction::mint_to(
+                &self.token_program,
+                &self.main_mint,
+                token_account,
+                &authority,
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Adds `amount` straight to the fund main token account, as if its holdings gained value.
+    pub async fn add_gains(&mut self, amount: u64) {
+        let fund_main_token_account = self.fund_main_token_account;
+        self.mint_to(&fund_main_token_account, amount).await;
+    }
+
+    /// A depositor with SOL for fees and `amount` of the main mint in the returned token account.
+    pub async fn depositor(&mut self, amount: u64) -> (Keypair, Pubkey) {
+        let depositor = Keypair::new();
+
+        self.process(
+            &[system_instruction::transfer(
+                &self.authority(),
+                &depositor.pubkey(),
+                1_000_000_000,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let token_account = self.create_token_account(&depositor.pubkey()).await;
+        self.mint_to(&token_account, amount).await;
+
+        (depositor, token_account)
+    }
+
+    /// Opens the position of `depositor` if it has none and deposits `amount` of the main mint.
+    pub async fn deposit(
+        &mut self,
+        depositor: &Keypair,
+        token_account: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let position = position_pda(&self.fund, &depositor.pubkey()).0;
+        let mut instructions = vec![];
+
+        let position_exists = self
+            .context
+            .banks_client
+            .get_account(position)
+            .await
+            .unwrap()
+            .is_some();
+        if !position_exists {
+            instructions.push(create_position(
+                position,
+                self.fund,
+                depositor.pubkey(),
+                None,
+                vec![],
+            ));
+        }
+
+        instructions.push(deposit(
+            position,
+            self.fund,
+            self.fund_main_token_account,
+            depositor.pubkey(),
+            *token_account,
+            self.main_mint,
+            self.token_program,
+            self.authority_token_account,
+            vec![],
+            amount,
+        ));
+
+        self.process(&instructions, &[depositor]).await
+    }
+
+    /// Burns `shares` of the position of `depositor` for their part of the main mint.
+    pub async fn withdraw(
+        &mut self,
+        depositor: &Keypair,
+        token_account: &Pubkey,
+        shares: u64,
+    ) -> Result<(), BanksClientError> {
+        self.process(
+            &[withdraw(
+                position_pda(&self.fund, &depositor.pubkey()).0,
+                depositor.pubkey(),
+                self.fund,
+                self.fund_main_token_account,
+                *token_account,
+                self.main_mint,
+                self.token_program,
+                self.authority_token_account,
+                vec![],
+                vec![],
+                shares,
+            )],
+            &[depositor],
+        )
+        .await
+    }
+
+    /// Creates the next payout of `amount` of the main mint, claimable indefinitely.
+    pub async fn create_payout(&mut self, amount: u64) -> Result<Pubkey, BanksClientError> {
+        self.create_payout_with_claim_period(amount, 0).await
+    }
+
+    /// Creates the next payout of `amount` of the main mint, claimable for `claim_period` seconds.
+    pub async fn create_payout_with_claim_period(
+        &mut self,
+        amount: u64,
+        claim_period: i64,
+    ) -> Result<Pubkey, BanksClientError> {
+        let counter = self.fund_state().await.payouts_counter + 1;
+        let payout = payout_pda(&self.fund, counter).0;
+
+        self.process(
+            &[create_payout(
+                self.authority(),
+                self.authority_token_account,
+                self.fund,
+                self.fund_main_token_account,
+                payout,
+                payout_token_account_pda(&payout).0,
+                self.main_mint,
+                self.fee_token_account,
+                self.token_program,
+                vec![],
+                amount,
+                claim_period,
+            )],
+            &[],
+        )
+        .await?;
+
+        Ok(payout)
+    }
+}
+
+fn add_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// The custom program error code `err` failed with, if any.
+pub fn custom_error(err: BanksClientError) -> Option<u32> {
+    match err.unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
@@ -0,0 +1,7 @@
+//! Tests that run the processors in a local bank, one module per feature.
+
+mod common;
+mod management_fee;
+mod payouts;
+mod performance_fee;
+mod token_2022;
@@ -0,0 +1,107 @@
+use solana_sdk::{signature::Keypair, signer::Signer};
+use steel::Pubkey;
+use yoko_program_api::prelude::*;
+
+use crate::common::{TestConfig, TestFund};
+
+/// A fund charging 10% a year, with 1_000_000 deposited by the returned depositor one year ago.
+async fn fund_a_year_after_deposit() -> (TestFund, Keypair, Pubkey) {
+    let mut test_fund = TestFund::start(TestConfig {
+        management_fee_bps: 1_000,
+        ..TestConfig::default()
+    })
+    .await;
+    let (depositor, token_account) = test_fund.depositor(1_000_000).await;
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    let now = test_fund.now().await;
+    test_fund.set_time(now + SECONDS_PER_YEAR).await;
+
+    (test_fund, depositor, token_account)
+}
+
+async fn accrue(test_fund: &mut TestFund) {
+    let instruction = accrue_management_fee(
+        test_fund.fund,
+        test_fund.fund_main_token_account,
+        test_fund.authority_token_account,
+        test_fund.main_mint,
+        test_fund.token_program,
+        vec![],
+    );
+
+    test_fund.process(&[instruction], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn deposit_collects_the_pending_management_fee() {
+    let (mut test_fund, _, _) = fund_a_year_after_deposit().await;
+    let (depositor, token_account) = test_fund.depositor(1_000_000).await;
+
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 100_000);
+
+    // The new shares are priced at the 900_000 left after the fee, and owe none of it.
+    let position = position_pda(&test_fund.fund, &depositor.pubkey()).0;
+    let position = test_fund.state::<Position>(&position).await;
+    assert_eq!(position.shares, 1_111_111);
+
+    let now = test_fund.now().await;
+    assert_eq!(
+        test_fund.fund_state().await.last_management_fee_accrual,
+        now
+    );
+}
+
+#[tokio::test]
+async fn withdraw_collects_the_pending_management_fee() {
+    let (mut test_fund, depositor, token_account) = fund_a_year_after_deposit().await;
+
+    test_fund
+        .withdraw(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 100_000);
+    assert_eq!(test_fund.balance(&token_account).await, 900_000);
+}
+
+#[tokio::test]
+async fn fee_beyond_the_main_balance_only_accrues_the_time_paid_for() {
+    let (mut test_fund, _, _) = fund_a_year_after_deposit().await;
+    let deposited_at = test_fund.fund_state().await.last_management_fee_accrual;
+
+    // Twenty years at 10% owe twice the fund value, so taking all of it pays for ten.
+    test_fund
+        .set_time(deposited_at + 20 * SECONDS_PER_YEAR)
+        .await;
+    accrue(&mut test_fund).await;
+
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 1_000_000);
+    assert_eq!(
+        test_fund.fund_state().await.last_management_fee_accrual,
+        deposited_at + 10 * SECONDS_PER_YEAR
+    );
+}
+
+#[tokio::test]
+async fn management_fee_accrues_while_paused() {
+    let (mut test_fund, _, _) = fund_a_year_after_deposit().await;
+    let pause = set_fund_paused(test_fund.fund, test_fund.authority(), true);
+    test_fund.process(&[pause], &[]).await.unwrap();
+
+    accrue(&mut test_fund).await;
+
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 100_000);
+}
@@ -0,0 +1,74 @@
+use solana_sdk::signer::Signer;
+use yoko_program_api::prelude::*;
+
+use crate::common::{TestConfig, TestFund};
+
+#[tokio::test]
+async fn deposit_after_claiming_the_payouts_the_position_is_behind_on() {
+    let mut test_fund = TestFund::start(TestConfig::default()).await;
+    let (depositor, token_account) = test_fund.depositor(2_000_000).await;
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    let expiring_payout = test_fund
+        .create_payout_with_claim_period(100_000, 100)
+        .await
+        .unwrap();
+    let payout = test_fund.create_payout(100_000).await.unwrap();
+
+    let now = test_fund.now().await;
+    test_fund.set_time(now + 101).await;
+    let reclaim = reclaim_payout(
+        test_fund.authority(),
+        test_fund.fund,
+        1,
+        test_fund.authority(),
+        test_fund.main_mint,
+        test_fund.token_program,
+    );
+    test_fund.process(&[reclaim], &[]).await.unwrap();
+
+    // The position is behind on both payouts, so it cannot deposit on its own.
+    assert!(test_fund
+        .deposit(&depositor, &token_account, 500_000)
+        .await
+        .is_err());
+
+    // Claiming in the same transaction skips the reclaimed payout and pays out the other one.
+    let position = position_pda(&test_fund.fund, &depositor.pubkey()).0;
+    let claim = claim_payouts(
+        position,
+        depositor.pubkey(),
+        token_account,
+        test_fund.fund,
+        test_fund.main_mint,
+        test_fund.token_program,
+        [expiring_payout, payout]
+            .into_iter()
+            .map(|payout| (payout, payout_token_account_pda(&payout).0))
+            .collect(),
+    );
+    let deposit = deposit(
+        position,
+        test_fund.fund,
+        test_fund.fund_main_token_account,
+        depositor.pubkey(),
+        token_account,
+        test_fund.main_mint,
+        test_fund.token_program,
+        test_fund.authority_token_account,
+        vec![],
+        500_000,
+    );
+    test_fund
+        .process(&[claim, deposit], &[&depositor])
+        .await
+        .unwrap();
+
+    assert_eq!(test_fund.balance(&token_account).await, 600_000);
+    let position = test_fund.state::<Position>(&position).await;
+    assert_eq!(position.payouts_counter, 2);
+    assert_eq!(position.shares, 1_000_000 + 500_000 * 1_000_000 / 900_000);
+}
@@ -0,0 +1,84 @@
+use yoko_program_api::prelude::*;
+
+use crate::common::{custom_error, TestConfig, TestFund};
+
+async fn performance_fund() -> TestFund {
+    TestFund::start(TestConfig {
+        fee_mode: FeeMode::Performance,
+        authority_fee_bps: 2_000,
+        ..TestConfig::default()
+    })
+    .await
+}
+
+#[tokio::test]
+async fn payout_charges_the_performance_fee_on_gains() {
+    let mut test_fund = performance_fund().await;
+    let (depositor, token_account) = test_fund.depositor(1_000_000).await;
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+    test_fund.add_gains(500_000).await;
+
+    let payout = test_fund.create_payout(300_000).await.unwrap();
+
+    // 20% of the 500_000 gained, and the payout gets the rest.
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 100_000);
+    assert_eq!(
+        test_fund
+            .balance(&payout_token_account_pda(&payout).0)
+            .await,
+        200_000
+    );
+
+    // The mark is crystallized at 1.5 per share and lowered by the 0.3 per share paid out.
+    let fund = test_fund.fund_state().await;
+    assert_eq!(fund.high_water_mark, 1_200_000_000);
+    assert_eq!(fund.performance_fee(1_200_000), Ok(0));
+}
+
+#[tokio::test]
+async fn payout_without_gains_only_lowers_the_mark() {
+    let mut test_fund = performance_fund().await;
+    let (depositor, token_account) = test_fund.depositor(1_000_000).await;
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    test_fund.create_payout(100_000).await.unwrap();
+
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 0);
+    assert_eq!(test_fund.fund_state().await.high_water_mark, 900_000_000);
+}
+
+#[tokio::test]
+async fn payout_smaller_than_the_performance_fee_is_rejected() {
+    let mut test_fund = performance_fund().await;
+    let (depositor, token_account) = test_fund.depositor(1_000_000).await;
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+    test_fund.add_gains(500_000).await;
+
+    // The 100_000 fee does not fit in a payout of 50_000.
+    let err = test_fund.create_payout(50_000).await.unwrap_err();
+    assert_eq!(
+        custom_error(err),
+        Some(YokoProgramError::PayoutBelowPerformanceFee as u32)
+    );
+    assert_eq!(
+        test_fund.fund_state().await.high_water_mark,
+        NAV_PER_SHARE_SCALE
+    );
+
+    // A payout that carries the whole fee crystallizes the gains.
+    test_fund.create_payout(100_000).await.unwrap();
+    let authority_token_account = test_fund.authority_token_account;
+    assert_eq!(test_fund.balance(&authority_token_account).await, 100_000);
+    assert_eq!(test_fund.fund_state().await.high_water_mark, 1_400_000_000);
+}
@@ -0,0 +1,64 @@
+use solana_sdk::signer::Signer;
+use yoko_program_api::prelude::*;
+
+use crate::common::{TestConfig, TestFund};
+
+/// A fund whose main mint is a Token-2022 mint withholding 1% of every transfer.
+async fn transfer_fee_fund() -> TestFund {
+    TestFund::start(TestConfig {
+        token_program: spl_token_2022::ID,
+        transfer_fee_bps: Some(100),
+        ..TestConfig::default()
+    })
+    .await
+}
+
+#[tokio::test]
+async fn deposit_credits_shares_net_of_the_transfer_fee() {
+    let mut test_fund = transfer_fee_fund().await;
+    let (depositor, token_account) = test_fund.depositor(2_000_000).await;
+
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    let fund_main_token_account = test_fund.fund_main_token_account;
+    assert_eq!(test_fund.balance(&fund_main_token_account).await, 990_000);
+
+    let position = position_pda(&test_fund.fund, &depositor.pubkey()).0;
+    let position = test_fund.state::<Position>(&position).await;
+    assert_eq!(position.shares, 990_000);
+    assert_eq!(position.deposited, 990_000);
+
+    // The second deposit is priced at the 990_000 the fund holds, not the 1_000_000 sent.
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    let fund = test_fund.fund_state().await;
+    assert_eq!(fund.total_shares, 1_980_000);
+    assert_eq!(fund.total_deposited, 1_980_000);
+}
+
+#[tokio::test]
+async fn withdraw_pays_out_of_a_token_2022_fund() {
+    let mut test_fund = transfer_fee_fund().await;
+    let (depositor, token_account) = test_fund.depositor(1_000_000).await;
+    test_fund
+        .deposit(&depositor, &token_account, 1_000_000)
+        .await
+        .unwrap();
+
+    test_fund
+        .withdraw(&depositor, &token_account, 990_000)
+        .await
+        .unwrap();
+
+    // The whole balance leaves the fund and the mint withholds 1% of it again.
+    let fund_main_token_account = test_fund.fund_main_token_account;
+    assert_eq!(test_fund.balance(&fund_main_token_account).await, 0);
+    assert_eq!(test_fund.balance(&token_account).await, 980_100);
+    assert_eq!(test_fund.fund_state().await.total_shares, 0);
+}
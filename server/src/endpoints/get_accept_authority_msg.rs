@@ -0,0 +1,66 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{sdk::accept_authority, state::Fund};
+
+#[derive(Deserialize)]
+pub struct GetAcceptAuthorityMsgPayload {
+    pub fund: String,
+}
+
+#[derive(Serialize)]
+pub struct GetAcceptAuthorityMsgResponse {
+    pub msg: String,
+}
+
+pub async fn get_accept_authority_msg(
+    Json(payload): Json<GetAcceptAuthorityMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetAcceptAuthorityMsgResponse>, (axum::http::StatusCode, String)> {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+    if fund_data.pending_authority == Pubkey::default() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "No pending manager for this fund".to_string(),
+        ));
+    }
+
+    let instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+        accept_authority(fund_pubkey, fund_data.pending_authority),
+    ];
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message = Message::try_compile(
+        &fund_data.pending_authority,
+        &instructions,
+        &[],
+        recent_blockhash,
+    )
+    .unwrap();
+
+    Ok(Json(GetAcceptAuthorityMsgResponse {
+        msg: base64::encode(message.serialize()),
+    }))
+}
@@ -4,17 +4,10 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
-use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account_idempotent,
-};
-use spl_token::instruction::close_account;
 use steel::{AccountDeserialize, Pubkey};
-use yoko_program_api::{
-    sdk::claim_payout,
-    state::{payout_pda, payout_token_account_pda, position_pda, Fund, Position},
-};
+use yoko_program_api::state::{position_pda, Fund, Position};
 
-use crate::WSOL;
+use crate::utils::get_claim_payouts_ixns;
 
 #[derive(Deserialize)]
 pub struct GetClaimPayoutMsgPayload {
@@ -48,47 +41,39 @@ pub async fn get_claim_payout_msg(
     let position_data = Position::try_from_bytes(&position_data).unwrap();
     let fund_data = rpc_client.get_account_data(&fund_pubkey).await.unwrap();
     let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
-    let depositor_main_token_account =
-        get_associated_token_address(&depositor_pubkey, &fund_data.main_mint);
-    let payout = payout_pda(&fund_pubkey, position_data.payouts_counter + 1).0;
-    let payout_main_token_account = payout_token_account_pda(&payout).0;
 
-    let claim_payout_ixn = claim_payout(
-        position,
-        depositor_pubkey,
-        payout,
-        payout_main_token_account,
-        depositor_main_token_account,
-        fund_pubkey,
-    );
-
-    let mut instructions = vec![];
-
-    instructions.extend([
-        ComputeBudgetInstruction::set_compute_unit_limit(250_000),
-        ComputeBudgetInstruction::set_compute_unit_price(500_000),
-        create_associated_token_account_idempotent(
-            &depositor_pubkey,
-            &depositor_pubkey,
-            &fund_data.main_mint,
-            &spl_token::ID,
-        ),
-        claim_payout_ixn,
-    ]);
+    if position_data.payouts_counter >= fund_data.payouts_counter {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "No payouts to claim".to_string(),
+        ));
+    }
 
-    if fund_data.main_mint == Pubkey::from_str(WSOL).unwrap() {
-        instructions.push(
-            close_account(
-                &spl_token::ID,
-                &depositor_main_token_account,
-                &depositor_pubkey,
-                &depositor_pubkey,
-                &[],
-            )
-            .unwrap(),
-        );
+    let compute_budget_ixns = |compute_units| {
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_units),
+            ComputeBudgetInstruction::set_compute_unit_price(500_000),
+        ]
+    };
+    let claims = get_claim_payouts_ixns(
+        &rpc_client,
+        &fund_pubkey,
+        fund_data,
+        &depositor_pubkey,
+        position_data,
+        &compute_budget_ixns(0),
+    )
+    .await?;
+    if claims.payouts == 0 {
+        return Err((
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            "No payout claim fits in a transaction".to_string(),
+        ));
     }
 
+    let mut instructions = compute_budget_ixns(claims.compute_units());
+    instructions.extend(claims.instructions);
+
     let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
 
     let message =
@@ -6,26 +6,104 @@ use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
 use steel::Pubkey;
 use yoko_program_api::{
-    sdk::create_fund,
-    state::{fund_pda, fund_token_account_pda},
+    consts::{MAX_AUTHORITY_FEE_BPS, MAX_BPS, MAX_MANAGEMENT_FEE_BPS},
+    sdk::{create_fund, create_mandate, set_redemption_rules},
+    state::{fund_pda, fund_token_account_pda, FeeMode},
 };
 
+use crate::utils::{get_funds_by_creator, get_token_mint};
+
 #[derive(Deserialize)]
 pub struct GetCreateFundMsgPayload {
     pub fund_manager: String,
     pub main_mint: String,
-    pub authority_fee: u64,
+    pub main_oracle: String,
+    pub authority_fee_bps: u64,
+    /// Charge `authority_fee_bps` only on gains above the high-water mark instead of on every payout.
+    #[serde(default)]
+    pub performance_fee: bool,
+    #[serde(default)]
+    pub hurdle_bps: u64,
+    /// Annual fee on the fund value.
+    #[serde(default)]
+    pub management_fee_bps: u64,
+    /// How far a swap may fall short of oracle prices. Zero disables the check.
+    #[serde(default)]
+    pub max_swap_deviation_bps: u64,
+    /// Largest total amount of the main mint the fund accepts. Zero disables the cap.
+    #[serde(default)]
+    pub deposit_cap: f64,
+    /// Smallest amount of the main mint a single deposit may be.
+    #[serde(default)]
+    pub min_deposit: f64,
+    /// Largest total amount of the main mint a single depositor may deposit. Zero disables the limit.
+    #[serde(default)]
+    pub max_position_deposit: f64,
+    /// Seconds after a deposit before it can be withdrawn.
+    #[serde(default)]
+    pub lockup_period: i64,
+    /// Seconds between requesting a redemption and being able to withdraw.
+    #[serde(default)]
+    pub notice_period: i64,
+    /// Mints the fund may hold besides the main mint. Any mint when omitted. Cannot be changed later.
+    pub mandate_mints: Option<Vec<String>>,
+    /// Defaults to the next id after the manager's previously created funds.
+    pub fund_id: Option<u64>,
 }
 
 #[derive(Serialize)]
 pub struct GetCreateFundMsgResponse {
     pub msg: String,
+    pub fund_pubkey: String,
 }
 
 pub async fn get_create_fund_msg(
     Json(payload): Json<GetCreateFundMsgPayload>,
     rpc_client: Arc<RpcClient>,
 ) -> Result<Json<GetCreateFundMsgResponse>, (axum::http::StatusCode, String)> {
+    if payload.authority_fee_bps > MAX_AUTHORITY_FEE_BPS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Authority fee must be at most {} bps",
+                MAX_AUTHORITY_FEE_BPS
+            ),
+        ));
+    }
+    if payload.hurdle_bps > MAX_BPS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Hurdle must be at most {} bps", MAX_BPS),
+        ));
+    }
+    if payload.management_fee_bps > MAX_MANAGEMENT_FEE_BPS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Management fee must be at most {} bps",
+                MAX_MANAGEMENT_FEE_BPS
+            ),
+        ));
+    }
+    if payload.max_swap_deviation_bps > MAX_BPS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Max swap deviation must be at most {} bps", MAX_BPS),
+        ));
+    }
+    if payload.deposit_cap < 0.0 || payload.min_deposit < 0.0 || payload.max_position_deposit < 0.0
+    {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Deposit limits must not be negative".to_string(),
+        ));
+    }
+    if payload.lockup_period < 0 || payload.notice_period < 0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Lockup and notice periods must not be negative".to_string(),
+        ));
+    }
     let fund_manager_pubkey = Pubkey::from_str(&payload.fund_manager).map_err(|_| {
         (
             axum::http::StatusCode::BAD_REQUEST,
@@ -38,7 +116,41 @@ pub async fn get_create_fund_msg(
             "Invalid main mint pubkey".to_string(),
         )
     })?;
-    let fund = fund_pda(&fund_manager_pubkey).0;
+    let main_oracle_pubkey = Pubkey::from_str(&payload.main_oracle).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid main oracle pubkey".to_string(),
+        )
+    })?;
+    let main_mint_data = get_token_mint(&rpc_client, &main_mint_pubkey).await?;
+    let main_mint_multiplier = 10u64.pow(main_mint_data.decimals as u32) as f64;
+    let mandate_mints = payload
+        .mandate_mints
+        .as_ref()
+        .map(|mints| {
+            mints
+                .iter()
+                .map(|mint| Pubkey::from_str(mint))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid mandate mint pubkey".to_string(),
+            )
+        })?;
+    let fund_id = match payload.fund_id {
+        Some(fund_id) => fund_id,
+        None => get_funds_by_creator(&rpc_client, &fund_manager_pubkey)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .iter()
+            .map(|(_, fund_data)| fund_data.id + 1)
+            .max()
+            .unwrap_or(0),
+    };
+    let fund = fund_pda(&fund_manager_pubkey, fund_id).0;
     let main_token_account = fund_token_account_pda(&fund, &main_mint_pubkey).0;
 
     let create_fund_ixn = create_fund(
@@ -46,7 +158,21 @@ pub async fn get_create_fund_msg(
         fund_manager_pubkey,
         main_mint_pubkey,
         main_token_account,
-        payload.authority_fee,
+        main_oracle_pubkey,
+        main_mint_data.token_program,
+        fund_id,
+        payload.authority_fee_bps,
+        if payload.performance_fee {
+            FeeMode::Performance
+        } else {
+            FeeMode::Payout
+        },
+        payload.hurdle_bps,
+        payload.management_fee_bps,
+        payload.max_swap_deviation_bps,
+        (payload.deposit_cap * main_mint_multiplier) as u64,
+        (payload.min_deposit * main_mint_multiplier) as u64,
+        (payload.max_position_deposit * main_mint_multiplier) as u64,
     );
 
     let mut instructions = vec![];
@@ -58,6 +184,19 @@ pub async fn get_create_fund_msg(
 
     instructions.push(create_fund_ixn);
 
+    if payload.lockup_period > 0 || payload.notice_period > 0 {
+        instructions.push(set_redemption_rules(
+            fund,
+            fund_manager_pubkey,
+            payload.lockup_period,
+            payload.notice_period,
+        ));
+    }
+
+    if let Some(mandate_mints) = mandate_mints {
+        instructions.push(create_mandate(fund, fund_manager_pubkey, mandate_mints));
+    }
+
     let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
 
     let message =
@@ -65,5 +204,6 @@ pub async fn get_create_fund_msg(
 
     Ok(Json(GetCreateFundMsgResponse {
         msg: base64::encode(message.serialize()),
+        fund_pubkey: fund.to_string(),
     }))
 }
@@ -3,27 +3,29 @@ use std::{str::FromStr, sync::Arc};
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, message::v0::Message, program_pack::Pack,
-};
-use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account_idempotent,
-};
-use spl_token::{instruction::close_account, state::Mint};
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::instruction::close_account;
 use steel::{AccountDeserialize, Pubkey};
 use yoko_program_api::{
-    sdk::create_payout,
+    sdk::{create_payout, create_payout_in_kind, fund_valuation_accounts},
     state::{fund_token_account_pda, payout_pda, payout_token_account_pda, Fund},
 };
 
-use crate::WSOL;
-
-const PROTOCOL_FEE_TOKEN_ACCOUNT_OWNER: &str = "H61JjSDPCwvAs1k2vaPAX6d917Pu4dPWykcexvXXzGph";
+use crate::{
+    utils::{check_not_paused, get_protocol_config, get_token_mint},
+    WSOL,
+};
 
 #[derive(Deserialize)]
 pub struct GetCreatePayoutMsgPayload {
     pub fund: String,
     pub amount: f64,
+    /// Mint to pay out in kind, one of the mints the fund holds. Defaults to the main mint.
+    pub mint: Option<String>,
+    /// Seconds depositors have to claim the payout before the fund authority can reclaim what is
+    /// left. Defaults to no deadline.
+    pub claim_period: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -49,33 +51,77 @@ pub async fn get_create_payout_msg(
     })?;
     let fund_data = rpc_client.get_account_data(&fund_pubkey).await.unwrap();
     let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
-    let fund_authority_token_account =
-        get_associated_token_address(&fund_data.authority, &fund_data.main_mint);
-    let fund_main_token_account = fund_token_account_pda(&fund_pubkey, &fund_data.main_mint).0;
-    let payout = payout_pda(&fund_pubkey, fund_data.payouts_counter + 1).0;
-    let payout_main_token_account = payout_token_account_pda(&payout).0;
+    check_not_paused(&rpc_client, fund_data).await?;
+    if fund_data.total_shares == 0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "The fund has no depositors to pay out to".to_string(),
+        ));
+    }
+    let protocol_config = get_protocol_config(&rpc_client).await.map_err(|_| {
+        (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            "Protocol config not found".to_string(),
+        )
+    })?;
+    let claim_period = payload.claim_period.unwrap_or(0);
+    if claim_period < 0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Claim period cannot be negative".to_string(),
+        ));
+    }
+    let mint_pubkey = match &payload.mint {
+        Some(mint) => Pubkey::from_str(mint).map_err(|_| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid mint pubkey".to_string(),
+            )
+        })?,
+        None => fund_data.main_mint,
+    };
+    let Some((oracle, _, _)) = fund_data.asset(&mint_pubkey) else {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "The fund can only pay out mints it holds".to_string(),
+        ));
+    };
+    let mint_data = get_token_mint(&rpc_client, &mint_pubkey).await?;
+    let fund_authority_token_account = mint_data.associated_token_address(&fund_data.authority);
 
-    let main_mint_data = rpc_client
-        .get_account_data(&fund_data.main_mint)
-        .await
-        .unwrap();
-    let main_mint_data = Mint::unpack(&main_mint_data).unwrap();
-    let amount = (payload.amount * (10u64.pow(main_mint_data.decimals as u32) as f64)) as u64;
-    let protocol_fee_token_account = get_associated_token_address(
-        &Pubkey::from_str(PROTOCOL_FEE_TOKEN_ACCOUNT_OWNER).unwrap(),
-        &fund_data.main_mint,
-    );
-    let create_payout_ixn = create_payout(
-        fund_data.authority,
-        fund_authority_token_account,
-        fund_pubkey,
-        fund_main_token_account,
-        payout,
-        payout_main_token_account,
-        fund_data.main_mint,
-        protocol_fee_token_account,
-        amount,
-    );
+    let amount = (payload.amount * (10u64.pow(mint_data.decimals as u32) as f64)) as u64;
+    let protocol_fee_token_account =
+        mint_data.associated_token_address(&protocol_config.fee_recipient);
+    let create_payout_ixn = if mint_pubkey == fund_data.main_mint {
+        let payout = payout_pda(&fund_pubkey, fund_data.payouts_counter + 1).0;
+        create_payout(
+            fund_data.authority,
+            fund_authority_token_account,
+            fund_pubkey,
+            fund_token_account_pda(&fund_pubkey, &fund_data.main_mint).0,
+            payout,
+            payout_token_account_pda(&payout).0,
+            fund_data.main_mint,
+            protocol_fee_token_account,
+            mint_data.token_program,
+            fund_valuation_accounts(&fund_pubkey, fund_data),
+            amount,
+            claim_period,
+        )
+    } else {
+        create_payout_in_kind(
+            fund_data.authority,
+            fund_authority_token_account,
+            fund_pubkey,
+            fund_data,
+            mint_pubkey,
+            oracle,
+            protocol_fee_token_account,
+            mint_data.token_program,
+            amount,
+            claim_period,
+        )
+    };
 
     let mut instructions = vec![];
 
@@ -85,13 +131,20 @@ pub async fn get_create_payout_msg(
         create_associated_token_account_idempotent(
             &fund_data.authority,
             &fund_data.authority,
-            &fund_data.main_mint,
-            &spl_token::ID,
+            &mint_pubkey,
+            &mint_data.token_program,
+        ),
+        // The protocol fee is paid in the payout mint, which the fee recipient may not hold yet.
+        create_associated_token_account_idempotent(
+            &fund_data.authority,
+            &protocol_config.fee_recipient,
+            &mint_pubkey,
+            &mint_data.token_program,
         ),
         create_payout_ixn,
     ]);
 
-    if fund_data.main_mint == Pubkey::from_str(WSOL).unwrap() {
+    if mint_pubkey == Pubkey::from_str(WSOL).unwrap() {
         instructions.push(
             close_account(
                 &spl_token::ID,
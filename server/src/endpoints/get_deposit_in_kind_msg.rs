@@ -0,0 +1,186 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{
+    oracle::convert_amount,
+    sdk::deposit_in_kind,
+    state::{position_pda, Fund},
+};
+
+use crate::utils::{
+    check_deposit_limits, check_not_paused, get_create_position_ixn, get_deposit_claim_ixns,
+    get_deposit_position, get_epoch, get_management_fee_ixns, get_price, get_token_mint,
+};
+
+#[derive(Deserialize)]
+pub struct GetDepositInKindMsgPayload {
+    pub fund: String,
+    pub depositor: String,
+    /// One of the mints the fund holds besides its main mint.
+    pub mint: String,
+    pub amount: f64,
+    /// Depositors allowed to open a position in a fund with a Merkle access policy, as shared by
+    /// the manager. Only needed for the first deposit.
+    pub merkle_members: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub struct GetDepositInKindMsgResponse {
+    pub msg: String,
+    /// Main mint amount the deposit is credited at, at current oracle prices. The program prices
+    /// the deposit again when the message lands.
+    pub credited_value: f64,
+    /// Amount of the main mint the depositor may still deposit after this deposit, or `None` when
+    /// the fund has no deposit cap or per-position limit.
+    pub remaining_capacity: Option<f64>,
+}
+
+pub async fn get_deposit_in_kind_msg(
+    Json(payload): Json<GetDepositInKindMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetDepositInKindMsgResponse>, (axum::http::StatusCode, String)> {
+    if payload.amount <= 0.0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Amount must be greater than 0".to_string(),
+        ));
+    }
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let depositor_pubkey = Pubkey::from_str(&payload.depositor).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid depositor pubkey".to_string(),
+        )
+    })?;
+    let mint_pubkey = Pubkey::from_str(&payload.mint).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid mint pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+    check_not_paused(&rpc_client, fund_data).await?;
+
+    if !fund_data.other_mints.contains(&mint_pubkey) {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "The fund only accepts in-kind deposits of mints it holds besides its main mint"
+                .to_string(),
+        ));
+    }
+    let (oracle, feed_id, _) = fund_data.asset(&mint_pubkey).unwrap();
+
+    let mint_data = get_token_mint(&rpc_client, &mint_pubkey).await?;
+    let amount = (payload.amount * (10u64.pow(mint_data.decimals as u32) as f64)) as u64;
+    // The fund is credited for what it receives after any transfer fee.
+    let epoch = get_epoch(&rpc_client).await?;
+    let received = amount - mint_data.transfer_fee(epoch, amount);
+
+    let price = get_price(&rpc_client, &oracle, &feed_id).await?;
+    let main_price =
+        get_price(&rpc_client, &fund_data.main_oracle, &fund_data.main_feed_id).await?;
+    let credited = convert_amount(
+        received,
+        mint_data.decimals,
+        &price,
+        fund_data.main_decimals,
+        &main_price,
+    )
+    .map_err(|_| {
+        (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to value the deposit".to_string(),
+        )
+    })?;
+
+    let main_mint_multiplier = 10u64.pow(fund_data.main_decimals as u32) as f64;
+    let (position_data, position_exists) =
+        get_deposit_position(&rpc_client, &fund_pubkey, fund_data, &depositor_pubkey).await;
+    let remaining_capacity =
+        check_deposit_limits(fund_data, &position_data, credited, main_mint_multiplier)?;
+
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+    ];
+
+    if !position_exists {
+        instructions.push(
+            get_create_position_ixn(
+                &rpc_client,
+                &fund_pubkey,
+                fund_data,
+                &depositor_pubkey,
+                payload.merkle_members.as_deref(),
+            )
+            .await?,
+        );
+    }
+
+    let main_mint_data = get_token_mint(&rpc_client, &fund_data.main_mint).await?;
+    instructions.extend(get_management_fee_ixns(
+        &depositor_pubkey,
+        fund_data,
+        &main_mint_data,
+    ));
+    let deposit_ixn = deposit_in_kind(
+        position_pda(&fund_pubkey, &depositor_pubkey).0,
+        fund_pubkey,
+        fund_data,
+        depositor_pubkey,
+        mint_data.associated_token_address(&depositor_pubkey),
+        mint_pubkey,
+        oracle,
+        mint_data.token_program,
+        main_mint_data.associated_token_address(&fund_data.authority),
+        main_mint_data.token_program,
+        amount,
+    );
+
+    // The program only takes deposits from positions that claimed every payout, so the claims go
+    // in the same message.
+    let claims = get_deposit_claim_ixns(
+        &rpc_client,
+        &fund_pubkey,
+        fund_data,
+        &depositor_pubkey,
+        &position_data,
+        &[instructions.as_slice(), std::slice::from_ref(&deposit_ixn)].concat(),
+    )
+    .await?;
+    instructions[0] =
+        ComputeBudgetInstruction::set_compute_unit_limit(300_000 + claims.compute_units());
+    instructions.extend(claims.instructions);
+    instructions.push(deposit_ixn);
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&depositor_pubkey, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetDepositInKindMsgResponse {
+        msg: base64::encode(message.serialize()),
+        credited_value: credited as f64 / main_mint_multiplier,
+        remaining_capacity: remaining_capacity.map(|remaining_capacity| {
+            (remaining_capacity - credited) as f64 / main_mint_multiplier
+        }),
+    }))
+}
@@ -3,32 +3,37 @@ use std::{str::FromStr, sync::Arc};
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, message::v0::Message, program_pack::Pack,
-    system_instruction,
-};
-use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account,
-};
-use spl_token::{instruction::sync_native, state::Mint};
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
 use steel::{AccountDeserialize, Pubkey};
 use yoko_program_api::{
-    sdk::{create_position, deposit},
+    sdk::{deposit, deposit_sol, fund_valuation_accounts},
     state::{fund_token_account_pda, position_pda, Fund},
 };
 
-use crate::WSOL;
+use crate::{
+    utils::{
+        check_deposit_limits, check_not_paused, get_create_position_ixn, get_deposit_claim_ixns,
+        get_deposit_position, get_management_fee_ixns, get_token_mint,
+    },
+    WSOL,
+};
 
 #[derive(Deserialize)]
 pub struct GetDepositMsgPayload {
     pub fund: String,
     pub depositor: String,
     pub amount: f64,
+    /// Depositors allowed to open a position in a fund with a Merkle access policy, as shared by
+    /// the manager. Only needed for the first deposit.
+    pub merkle_members: Option<Vec<String>>,
 }
 
 #[derive(Serialize)]
 pub struct GetDepositMsgResponse {
     pub msg: String,
+    /// Amount of the main mint the depositor may still deposit after this deposit, or `None` when
+    /// the fund has no deposit cap or per-position limit.
+    pub remaining_capacity: Option<f64>,
 }
 
 pub async fn get_deposit_msg(
@@ -55,26 +60,47 @@ pub async fn get_deposit_msg(
     })?;
     let fund_data = rpc_client.get_account_data(&fund_pubkey).await.unwrap();
     let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+    check_not_paused(&rpc_client, fund_data).await?;
     let fund_main_token_account = fund_token_account_pda(&fund_pubkey, &fund_data.main_mint).0;
-    let main_mint_data = rpc_client
-        .get_account_data(&fund_data.main_mint)
-        .await
-        .unwrap();
-    let main_mint_data = Mint::unpack(&main_mint_data).unwrap();
+    let main_mint_data = get_token_mint(&rpc_client, &fund_data.main_mint).await?;
     let position_pubkey = position_pda(&fund_pubkey, &depositor_pubkey).0;
-    let depositor_token_account =
-        get_associated_token_address(&depositor_pubkey, &fund_data.main_mint);
-
-    let amount = (payload.amount * (10u64.pow(main_mint_data.decimals as u32) as f64)) as u64;
-
-    let deposit_ixn = deposit(
-        position_pubkey,
-        fund_pubkey,
-        fund_main_token_account,
-        depositor_pubkey,
-        depositor_token_account,
-        amount,
-    );
+    let depositor_token_account = main_mint_data.associated_token_address(&depositor_pubkey);
+    let fund_authority_token_account =
+        main_mint_data.associated_token_address(&fund_data.authority);
+
+    let main_mint_multiplier = 10u64.pow(main_mint_data.decimals as u32) as f64;
+    let amount = (payload.amount * main_mint_multiplier) as u64;
+
+    let (position_data, position_exists) =
+        get_deposit_position(&rpc_client, &fund_pubkey, fund_data, &depositor_pubkey).await;
+    let remaining_capacity =
+        check_deposit_limits(fund_data, &position_data, amount, main_mint_multiplier)?;
+
+    // Lamports are wrapped inside the program, so WSOL depositors need no token account.
+    let deposit_ixn = if fund_data.main_mint == Pubkey::from_str(WSOL).unwrap() {
+        deposit_sol(
+            position_pubkey,
+            fund_pubkey,
+            fund_main_token_account,
+            depositor_pubkey,
+            fund_authority_token_account,
+            fund_valuation_accounts(&fund_pubkey, fund_data),
+            amount,
+        )
+    } else {
+        deposit(
+            position_pubkey,
+            fund_pubkey,
+            fund_main_token_account,
+            depositor_pubkey,
+            depositor_token_account,
+            fund_data.main_mint,
+            main_mint_data.token_program,
+            fund_authority_token_account,
+            fund_valuation_accounts(&fund_pubkey, fund_data),
+            amount,
+        )
+    };
 
     let mut instructions = vec![];
 
@@ -83,39 +109,39 @@ pub async fn get_deposit_msg(
         ComputeBudgetInstruction::set_compute_unit_price(500_000),
     ]);
 
-    if fund_data.main_mint
-        == Pubkey::from_str(WSOL).unwrap()
-    {
-        match rpc_client.get_account_data(&depositor_token_account).await {
-            Err(_) => {
-                instructions.push(create_associated_token_account(
-                    &depositor_pubkey,
-                    &depositor_pubkey,
-                    &fund_data.main_mint,
-                    &spl_token::ID,
-                ));
-            }
-            Ok(_) => {}
-        }
-
-        instructions.push(system_instruction::transfer(
-            &depositor_pubkey,
-            &depositor_token_account,
-            amount,
-        ));
-
-        instructions.push(sync_native(&spl_token::ID, &depositor_token_account).unwrap());
-    }
-
-    match rpc_client.get_account_data(&position_pubkey).await {
-        Err(_) => {
-            let create_position_ixn =
-                create_position(position_pubkey, fund_pubkey, depositor_pubkey);
-            instructions.push(create_position_ixn);
-        }
-        Ok(_) => {}
+    if !position_exists {
+        instructions.push(
+            get_create_position_ixn(
+                &rpc_client,
+                &fund_pubkey,
+                fund_data,
+                &depositor_pubkey,
+                payload.merkle_members.as_deref(),
+            )
+            .await?,
+        );
     }
 
+    instructions.extend(get_management_fee_ixns(
+        &depositor_pubkey,
+        fund_data,
+        &main_mint_data,
+    ));
+
+    // The program only takes deposits from positions that claimed every payout, so the claims go
+    // in the same message.
+    let claims = get_deposit_claim_ixns(
+        &rpc_client,
+        &fund_pubkey,
+        fund_data,
+        &depositor_pubkey,
+        &position_data,
+        &[instructions.as_slice(), std::slice::from_ref(&deposit_ixn)].concat(),
+    )
+    .await?;
+    instructions[0] =
+        ComputeBudgetInstruction::set_compute_unit_limit(250_000 + claims.compute_units());
+    instructions.extend(claims.instructions);
     instructions.push(deposit_ixn);
 
     let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
@@ -125,5 +151,7 @@ pub async fn get_deposit_msg(
 
     Ok(Json(GetDepositMsgResponse {
         msg: base64::encode(message.serialize()),
+        remaining_capacity: remaining_capacity
+            .map(|remaining_capacity| (remaining_capacity - amount) as f64 / main_mint_multiplier),
     }))
 }
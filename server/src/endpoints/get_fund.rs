@@ -1,19 +1,24 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use axum::Json;
-use reqwest;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::program_pack::Pack;
-use spl_token::state::Account;
 use steel::{AccountDeserialize, Pubkey};
-use yoko_program_api::state::{fund_pda, fund_token_account_pda, Fund};
+use yoko_program_api::{
+    state::{fund_token_account_pda, mandate_pda, AccessMode, Fund, Mandate},
+    token::unpack_token_account,
+};
 
+use crate::utils::get_protocol_config;
 
 #[derive(Deserialize)]
 pub struct GetFundPayload {
-    pub fund_manager: String,
+    pub fund: String,
 }
 
 #[derive(Serialize)]
@@ -27,10 +32,44 @@ pub struct Token {
 #[derive(Serialize)]
 pub struct GetFundResponse {
     pub fund_pubkey: String,
+    pub fund_id: u64,
     pub manager: String,
-    pub total_deposited: u64,
+    pub pending_manager: Option<String>,
+    pub total_shares: u64,
     pub payouts_counter: u64,
-    pub manager_fee: u64,
+    pub manager_fee_bps: u64,
+    pub performance_fee: bool,
+    pub hurdle_bps: u64,
+    /// Net asset value per share in main mint base units, scaled by 1e9.
+    pub high_water_mark: u64,
+    /// Performance fee in main mint base units the manager would take if a payout was created now.
+    pub accrued_performance_fee: u64,
+    pub management_fee_bps: u64,
+    /// Management fee in main mint base units accrued since the last `AccrueManagementFee`.
+    pub pending_management_fee: u64,
+    pub max_swap_deviation_bps: u64,
+    /// Main mint base units deposited and not yet withdrawn.
+    pub total_deposited: u64,
+    pub deposit_cap: u64,
+    pub min_deposit: u64,
+    pub max_position_deposit: u64,
+    /// Main mint base units the fund accepts before reaching `deposit_cap`, or `None` when it has
+    /// no cap.
+    pub remaining_capacity: Option<u64>,
+    /// Seconds after a deposit before it can be withdrawn.
+    pub lockup_period: i64,
+    /// Seconds between requesting a redemption and being able to withdraw.
+    pub notice_period: i64,
+    /// Who may open a position: 0 anyone, 1 allowlisted depositors, 2 depositors with a Merkle
+    /// proof, 3 holders of `gate_mint`.
+    pub access_mode: u8,
+    pub gate_mint: Option<String>,
+    /// Deposits, swaps and payouts are blocked while the fund or the protocol is paused. Claims
+    /// and withdrawals stay available.
+    pub paused: bool,
+    pub protocol_paused: bool,
+    /// Mints the fund may hold besides the main mint, or `None` when it may hold any mint.
+    pub mandate: Option<Vec<String>>,
     pub main_token: Token,
     pub other_tokens: Vec<Token>,
     pub total_usd_amount: f64,
@@ -41,7 +80,12 @@ pub async fn get_fund(
     rpc_client: Arc<RpcClient>,
     helius_url: String,
 ) -> Result<Json<GetFundResponse>, (axum::http::StatusCode, String)> {
-    let fund = fund_pda(&Pubkey::from_str(&payload.fund_manager).unwrap()).0;
+    let fund = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
 
     let fund_data = match rpc_client.get_account_data(&fund).await {
         Ok(data) => data,
@@ -63,12 +107,13 @@ pub async fn get_fund(
         .get_account_data(&main_token_account)
         .await
         .unwrap();
-    let main_token_account_data = Account::unpack(&main_token_account_data).unwrap();
+    let main_token_account_data = unpack_token_account(&main_token_account_data).unwrap();
 
     let mut total_usd_amount = 0.0;
     let main_ui_amount =
         main_token_account_data.amount as f64 / 10.0_f64.powi(main_asset.decimals as i32);
-    let main_usd_amount = (main_ui_amount * main_asset.price_info.price_per_token * 100.0).round() / 100.0;
+    let main_usd_amount =
+        (main_ui_amount * main_asset.price_info.price_per_token * 100.0).round() / 100.0;
     total_usd_amount += main_usd_amount;
 
     let main_token = Token {
@@ -87,7 +132,7 @@ pub async fn get_fund(
             .unwrap();
         let token_account = fund_token_account_pda(&fund, mint).0;
         let token_account_data = rpc_client.get_account_data(&token_account).await.unwrap();
-        let token_account_data = Account::unpack(&token_account_data).unwrap();
+        let token_account_data = unpack_token_account(&token_account_data).unwrap();
 
         let ui_amount = token_account_data.amount as f64 / 10.0_f64.powi(asset.decimals as i32);
         let usd_amount = (ui_amount * asset.price_info.price_per_token * 100.0).round() / 100.0;
@@ -101,14 +146,84 @@ pub async fn get_fund(
         });
     }
 
+    let mandate = if fund_data.has_mandate() {
+        let mandate_data = rpc_client
+            .get_account_data(&mandate_pda(&fund).0)
+            .await
+            .unwrap();
+        let mandate_data = Mandate::try_from_bytes(&mandate_data).unwrap();
+        Some(
+            mandate_data
+                .mints
+                .iter()
+                .map(|mint| mint.to_string())
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    // Fund value in main mint base units, estimated from the same USD prices as above. It can
+    // differ slightly from the on-chain oracle valuation used when fees are charged.
+    let estimated_value = if main_asset.price_info.price_per_token > 0.0 {
+        (total_usd_amount / main_asset.price_info.price_per_token
+            * 10.0_f64.powi(main_asset.decimals as i32)) as u64
+    } else {
+        main_token_account_data.amount
+    };
+    let accrued_performance_fee = if fund_data.has_performance_fee() {
+        fund_data.performance_fee(estimated_value).unwrap_or(0)
+    } else {
+        0
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(fund_data.last_management_fee_accrual);
+    let pending_management_fee = fund_data
+        .pending_management_fee(estimated_value, now)
+        .unwrap_or(0);
+
+    let protocol_paused = get_protocol_config(&rpc_client)
+        .await
+        .map(|protocol_config| protocol_config.is_paused())
+        .unwrap_or(false);
+
     other_tokens.sort_by(|a, b| b.usd_amount.partial_cmp(&a.usd_amount).unwrap());
 
     Ok(Json(GetFundResponse {
         fund_pubkey: fund.to_string(),
+        fund_id: fund_data.id,
         manager: fund_data.authority.to_string(),
-        total_deposited: fund_data.total_deposited,
+        pending_manager: (fund_data.pending_authority != Pubkey::default())
+            .then(|| fund_data.pending_authority.to_string()),
+        total_shares: fund_data.total_shares,
         payouts_counter: fund_data.payouts_counter,
-        manager_fee: fund_data.authority_fee,
+        manager_fee_bps: fund_data.authority_fee_bps,
+        performance_fee: fund_data.has_performance_fee(),
+        hurdle_bps: fund_data.hurdle_bps,
+        high_water_mark: fund_data.high_water_mark,
+        accrued_performance_fee,
+        management_fee_bps: fund_data.management_fee_bps,
+        pending_management_fee,
+        max_swap_deviation_bps: fund_data.max_swap_deviation_bps,
+        total_deposited: fund_data.total_deposited,
+        deposit_cap: fund_data.deposit_cap,
+        min_deposit: fund_data.min_deposit,
+        max_position_deposit: fund_data.max_position_deposit,
+        remaining_capacity: (fund_data.deposit_cap != 0).then(|| {
+            fund_data
+                .deposit_cap
+                .saturating_sub(fund_data.total_deposited)
+        }),
+        lockup_period: fund_data.lockup_period,
+        notice_period: fund_data.notice_period,
+        access_mode: fund_data.access_mode,
+        gate_mint: (fund_data.access_mode() == AccessMode::TokenGate)
+            .then(|| fund_data.gate_mint.to_string()),
+        paused: fund_data.is_paused(),
+        protocol_paused,
+        mandate,
         main_token,
         other_tokens,
         total_usd_amount,
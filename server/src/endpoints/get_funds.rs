@@ -0,0 +1,54 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use steel::Pubkey;
+
+use crate::utils::get_funds_by_authority;
+
+#[derive(Deserialize)]
+pub struct GetFundsPayload {
+    pub fund_manager: String,
+}
+
+#[derive(Serialize)]
+pub struct FundSummary {
+    pub fund_pubkey: String,
+    pub fund_id: u64,
+    pub main_mint: String,
+    pub total_shares: u64,
+}
+
+#[derive(Serialize)]
+pub struct GetFundsResponse {
+    pub funds: Vec<FundSummary>,
+}
+
+pub async fn get_funds(
+    Json(payload): Json<GetFundsPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetFundsResponse>, (axum::http::StatusCode, String)> {
+    let fund_manager_pubkey = Pubkey::from_str(&payload.fund_manager).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund manager pubkey".to_string(),
+        )
+    })?;
+
+    let funds = get_funds_by_authority(&rpc_client, &fund_manager_pubkey)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(GetFundsResponse {
+        funds: funds
+            .into_iter()
+            .map(|(fund, fund_data)| FundSummary {
+                fund_pubkey: fund.to_string(),
+                fund_id: fund_data.id,
+                main_mint: fund_data.main_mint.to_string(),
+                total_shares: fund_data.total_shares,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,80 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::state::{position_pda, Fund, Position};
+
+#[derive(Deserialize)]
+pub struct GetPositionPayload {
+    pub fund: String,
+    pub depositor: String,
+}
+
+#[derive(Serialize)]
+pub struct GetPositionResponse {
+    pub position_pubkey: String,
+    pub shares: u64,
+    pub payouts_counter: u64,
+    /// Main mint base units deposited and not yet withdrawn.
+    pub deposited: u64,
+    pub last_deposit_ts: i64,
+    /// Unix timestamp from which the fund lockup no longer blocks withdrawing.
+    pub unlocks_at: i64,
+    pub redemption_requested_at: Option<i64>,
+    /// Unix timestamp from which the position can be withdrawn, or `None` if the fund requires a
+    /// redemption request first.
+    pub withdrawable_at: Option<i64>,
+}
+
+pub async fn get_position(
+    Json(payload): Json<GetPositionPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetPositionResponse>, (axum::http::StatusCode, String)> {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let depositor_pubkey = Pubkey::from_str(&payload.depositor).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid depositor pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+    let position_pubkey = position_pda(&fund_pubkey, &depositor_pubkey).0;
+    let position_data = rpc_client
+        .get_account_data(&position_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Position not found".to_string(),
+            )
+        })?;
+    let position_data = Position::try_from_bytes(&position_data).unwrap();
+
+    Ok(Json(GetPositionResponse {
+        position_pubkey: position_pubkey.to_string(),
+        shares: position_data.shares,
+        payouts_counter: position_data.payouts_counter,
+        deposited: position_data.deposited,
+        last_deposit_ts: position_data.last_deposit_ts,
+        unlocks_at: position_data.unlocks_at(fund_data),
+        redemption_requested_at: (position_data.redemption_requested_at != 0)
+            .then_some(position_data.redemption_requested_at),
+        withdrawable_at: position_data.withdrawable_at(fund_data),
+    }))
+}
@@ -0,0 +1,145 @@
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{
+    sdk::{create_fund_token_account, reclaim_payout},
+    state::{fund_token_account_pda, payout_pda, payout_token_account_pda, Fund, Payout},
+};
+
+use crate::utils::{
+    check_not_paused, check_protocol_not_paused, get_token_balance, get_token_mint,
+};
+
+#[derive(Deserialize)]
+pub struct GetReclaimPayoutMsgPayload {
+    pub fund: String,
+    /// Number of the payout, as counted by `Fund.payouts_counter` when it was created.
+    pub counter: u64,
+    /// Price account for the payout mint, required when tokens are left to reclaim and the fund
+    /// no longer holds that mint.
+    pub mint_oracle: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetReclaimPayoutMsgResponse {
+    pub msg: String,
+}
+
+pub async fn get_reclaim_payout_msg(
+    Json(payload): Json<GetReclaimPayoutMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetReclaimPayoutMsgResponse>, (axum::http::StatusCode, String)> {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+    check_protocol_not_paused(&rpc_client).await?;
+    let payout_data = rpc_client
+        .get_account_data(&payout_pda(&fund_pubkey, payload.counter).0)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Payout not found or already closed".to_string(),
+            )
+        })?;
+    let payout_data = Payout::try_from_bytes(&payout_data).unwrap();
+
+    if payout_data.claim_deadline == 0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Payout has no claim deadline".to_string(),
+        ));
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0);
+    if now <= payout_data.claim_deadline {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Payout can be reclaimed in {} seconds",
+                payout_data.claim_deadline - now
+            ),
+        ));
+    }
+
+    let mint_data = get_token_mint(&rpc_client, &payout_data.mint).await?;
+
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+    ];
+
+    // A swap closes the fund token account of a mint once the fund sold all of it, so it is
+    // created again to receive what is left of the payout.
+    let payout_token_account =
+        payout_token_account_pda(&payout_pda(&fund_pubkey, payload.counter).0).0;
+    if fund_data.asset(&payout_data.mint).is_none()
+        && get_token_balance(&rpc_client, &payout_token_account).await? > 0
+    {
+        let mint_oracle = payload
+            .mint_oracle
+            .as_deref()
+            .ok_or((
+                axum::http::StatusCode::BAD_REQUEST,
+                "Mint oracle is required for a mint the fund no longer holds".to_string(),
+            ))?
+            .parse::<Pubkey>()
+            .map_err(|_| {
+                (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    "Invalid mint oracle pubkey".to_string(),
+                )
+            })?;
+        check_not_paused(&rpc_client, fund_data).await?;
+
+        instructions.push(create_fund_token_account(
+            fund_pubkey,
+            fund_data.authority,
+            fund_token_account_pda(&fund_pubkey, &payout_data.mint).0,
+            payout_data.mint,
+            mint_oracle,
+            mint_data.token_program,
+        ));
+    }
+
+    instructions.push(reclaim_payout(
+        fund_data.authority,
+        fund_pubkey,
+        payload.counter,
+        payout_data.rent_payer,
+        payout_data.mint,
+        mint_data.token_program,
+    ));
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&fund_data.authority, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetReclaimPayoutMsgResponse {
+        msg: base64::encode(message.serialize()),
+    }))
+}
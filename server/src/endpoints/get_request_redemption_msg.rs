@@ -0,0 +1,53 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use steel::Pubkey;
+use yoko_program_api::{sdk::request_redemption, state::position_pda};
+
+#[derive(Deserialize)]
+pub struct GetRequestRedemptionMsgPayload {
+    pub fund: String,
+    pub depositor: String,
+}
+
+#[derive(Serialize)]
+pub struct GetRequestRedemptionMsgResponse {
+    pub msg: String,
+}
+
+pub async fn get_request_redemption_msg(
+    Json(payload): Json<GetRequestRedemptionMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetRequestRedemptionMsgResponse>, (axum::http::StatusCode, String)> {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let depositor_pubkey = Pubkey::from_str(&payload.depositor).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid depositor pubkey".to_string(),
+        )
+    })?;
+    let position_pubkey = position_pda(&fund_pubkey, &depositor_pubkey).0;
+
+    let instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+        request_redemption(position_pubkey, depositor_pubkey),
+    ];
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&depositor_pubkey, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetRequestRedemptionMsgResponse {
+        msg: base64::encode(message.serialize()),
+    }))
+}
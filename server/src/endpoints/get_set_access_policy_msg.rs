@@ -0,0 +1,122 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, hash::Hash, message::v0::Message};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{
+    merkle::merkle_root,
+    sdk::set_access_policy,
+    state::{AccessMode, Fund},
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Access {
+    Open,
+    Allowlist,
+    Merkle,
+    TokenGate,
+}
+
+#[derive(Deserialize)]
+pub struct GetSetAccessPolicyMsgPayload {
+    pub fund: String,
+    pub access: Access,
+    /// Depositors allowed to open a position, required for `merkle`. Depositors need the same
+    /// list to build their proofs.
+    pub members: Option<Vec<String>>,
+    /// Mint depositors have to hold, required for `token_gate`.
+    pub gate_mint: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetSetAccessPolicyMsgResponse {
+    pub msg: String,
+    pub merkle_root: Option<String>,
+}
+
+pub async fn get_set_access_policy_msg(
+    Json(payload): Json<GetSetAccessPolicyMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetSetAccessPolicyMsgResponse>, (axum::http::StatusCode, String)> {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+
+    let mut root = [0; 32];
+    let mut gate_mint = Pubkey::default();
+    let access_mode = match payload.access {
+        Access::Open => AccessMode::Open,
+        Access::Allowlist => AccessMode::Allowlist,
+        Access::Merkle => {
+            let members = payload
+                .members
+                .as_ref()
+                .filter(|members| !members.is_empty())
+                .ok_or((
+                    axum::http::StatusCode::BAD_REQUEST,
+                    "Members are required for a Merkle access policy".to_string(),
+                ))?
+                .iter()
+                .map(|member| Pubkey::from_str(member))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| {
+                    (
+                        axum::http::StatusCode::BAD_REQUEST,
+                        "Invalid member pubkey".to_string(),
+                    )
+                })?;
+            root = merkle_root(&members);
+            AccessMode::Merkle
+        }
+        Access::TokenGate => {
+            gate_mint = payload
+                .gate_mint
+                .as_deref()
+                .and_then(|gate_mint| Pubkey::from_str(gate_mint).ok())
+                .ok_or((
+                    axum::http::StatusCode::BAD_REQUEST,
+                    "Invalid gate mint pubkey".to_string(),
+                ))?;
+            AccessMode::TokenGate
+        }
+    };
+
+    let instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+        set_access_policy(
+            fund_pubkey,
+            fund_data.authority,
+            access_mode,
+            root,
+            gate_mint,
+        ),
+    ];
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&fund_data.authority, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetSetAccessPolicyMsgResponse {
+        msg: base64::encode(message.serialize()),
+        merkle_root: (access_mode == AccessMode::Merkle)
+            .then(|| Hash::new_from_array(root).to_string()),
+    }))
+}
@@ -0,0 +1,56 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{sdk::set_fund_paused, state::Fund};
+
+#[derive(Deserialize)]
+pub struct GetSetFundPausedMsgPayload {
+    pub fund: String,
+    pub paused: bool,
+}
+
+#[derive(Serialize)]
+pub struct GetSetFundPausedMsgResponse {
+    pub msg: String,
+}
+
+pub async fn get_set_fund_paused_msg(
+    Json(payload): Json<GetSetFundPausedMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetSetFundPausedMsgResponse>, (axum::http::StatusCode, String)> {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+
+    let instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+        set_fund_paused(fund_pubkey, fund_data.authority, payload.paused),
+    ];
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&fund_data.authority, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetSetFundPausedMsgResponse {
+        msg: base64::encode(message.serialize()),
+    }))
+}
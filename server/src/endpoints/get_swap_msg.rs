@@ -3,19 +3,23 @@ use std::{str::FromStr, sync::Arc};
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::program_pack::Pack;
-use spl_token::state::Mint;
-use steel::Pubkey;
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::state::{mandate_pda, Fund, Mandate};
 
-use crate::utils::get_swap_message;
+use crate::utils::{
+    check_not_paused, check_swap_risk_limits, get_epoch, get_feed_id, get_swap_message,
+    get_token_mint,
+};
 
 #[derive(Deserialize)]
 pub struct GetSwapMsgPayload {
-    pub fund_manager: String,
+    pub fund: String,
     pub from_mint: String,
     pub to_mint: String,
     pub in_amount: f64,
     pub quote: serde_json::Value,
+    /// Price account for `to_mint`, required when the fund does not hold that mint yet.
+    pub to_mint_oracle: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -33,10 +37,10 @@ pub async fn get_swap_msg(
             "In amount must be greater than 0".to_string(),
         ));
     }
-    let fund_manager_pubkey = Pubkey::from_str(&payload.fund_manager).map_err(|_| {
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
         (
             axum::http::StatusCode::BAD_REQUEST,
-            "Invalid fund manager pubkey".to_string(),
+            "Invalid fund pubkey".to_string(),
         )
     })?;
     let from_mint_pubkey = Pubkey::from_str(&payload.from_mint).map_err(|_| {
@@ -51,21 +55,112 @@ pub async fn get_swap_msg(
             "Invalid to mint pubkey".to_string(),
         )
     })?;
-    let from_mint_data = rpc_client
-        .get_account_data(&from_mint_pubkey)
+    let to_mint_oracle_pubkey = payload
+        .to_mint_oracle
+        .as_deref()
+        .map(Pubkey::from_str)
+        .transpose()
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid to mint oracle pubkey".to_string(),
+            )
+        })?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
         .await
-        .unwrap();
-    let from_mint_data = Mint::unpack(&from_mint_data).unwrap();
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let mut fund_data = *Fund::try_from_bytes(&fund_data).unwrap();
+    check_not_paused(&rpc_client, &fund_data).await?;
+    if to_mint_pubkey != fund_data.main_mint
+        && !fund_data.other_mints.contains(&to_mint_pubkey)
+        && to_mint_oracle_pubkey.is_none()
+    {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "To mint oracle is required for a mint the fund does not hold".to_string(),
+        ));
+    }
+    if fund_data.has_mandate() && to_mint_pubkey != fund_data.main_mint {
+        let mandate_data = rpc_client
+            .get_account_data(&mandate_pda(&fund_pubkey).0)
+            .await
+            .unwrap();
+        let mandate_data = Mandate::try_from_bytes(&mandate_data).unwrap();
+        if !mandate_data.mints.contains(&to_mint_pubkey) {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                "To mint is not in the fund mandate".to_string(),
+            ));
+        }
+    }
+    let epoch = get_epoch(&rpc_client).await?;
+    let from_mint_data = get_token_mint(&rpc_client, &from_mint_pubkey).await?;
+    let to_mint_data = get_token_mint(&rpc_client, &to_mint_pubkey).await?;
     let in_amount = (payload.in_amount * (10u64.pow(from_mint_data.decimals as u32) as f64)) as u64;
+    // The route swaps what reaches the manager's token account after any transfer fee.
+    let swap_amount = in_amount - from_mint_data.transfer_fee(epoch, in_amount);
+    let quote_in_amount = payload.quote["inAmount"]
+        .as_str()
+        .and_then(|amount| amount.parse::<u64>().ok());
+    if quote_in_amount != Some(swap_amount) {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Quote must swap {} after the transfer fee", swap_amount),
+        ));
+    }
+    // The quote's output after slippage, enforced on-chain on what reaches the fund after any
+    // transfer fee.
+    let min_out_amount = payload.quote["otherAmountThreshold"]
+        .as_str()
+        .and_then(|amount| amount.parse::<u64>().ok())
+        .ok_or((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Quote is missing otherAmountThreshold".to_string(),
+        ))?;
+    let min_out_amount = min_out_amount - to_mint_data.transfer_fee(epoch, min_out_amount);
+    let out_amount = payload.quote["outAmount"]
+        .as_str()
+        .and_then(|amount| amount.parse::<u64>().ok())
+        .map(|amount| amount - to_mint_data.transfer_fee(epoch, amount))
+        .unwrap_or(min_out_amount);
+
+    if to_mint_pubkey != fund_data.main_mint && !fund_data.other_mints.contains(&to_mint_pubkey) {
+        let to_mint_oracle_pubkey = to_mint_oracle_pubkey.unwrap();
+        let to_mint_feed_id = get_feed_id(&rpc_client, &to_mint_oracle_pubkey).await?;
+        fund_data.insert_other_mint(
+            to_mint_pubkey,
+            to_mint_oracle_pubkey,
+            to_mint_feed_id,
+            to_mint_data.decimals,
+        );
+    }
+    check_swap_risk_limits(
+        &rpc_client,
+        &fund_pubkey,
+        &fund_data,
+        &from_mint_pubkey,
+        &to_mint_pubkey,
+        in_amount,
+        out_amount,
+    )
+    .await?;
 
     Ok(Json(GetSwapMsgResponse {
         msg: get_swap_message(
             rpc_client,
-            fund_manager_pubkey,
+            fund_pubkey,
             from_mint_pubkey,
             to_mint_pubkey,
             in_amount,
+            min_out_amount,
             payload.quote,
+            to_mint_oracle_pubkey,
         )
         .await,
     }))
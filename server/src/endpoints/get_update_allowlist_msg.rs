@@ -0,0 +1,97 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{
+    sdk::{add_to_allowlist, remove_from_allowlist},
+    state::Fund,
+};
+
+/// Keeps the message within the transaction size limit.
+const MAX_ALLOWLIST_UPDATES: usize = 16;
+
+#[derive(Deserialize)]
+pub struct GetUpdateAllowlistMsgPayload {
+    pub fund: String,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetUpdateAllowlistMsgResponse {
+    pub msg: String,
+}
+
+pub async fn get_update_allowlist_msg(
+    Json(payload): Json<GetUpdateAllowlistMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetUpdateAllowlistMsgResponse>, (axum::http::StatusCode, String)> {
+    let updates = payload.add.len() + payload.remove.len();
+    if updates == 0 || updates > MAX_ALLOWLIST_UPDATES {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Between 1 and {} depositors can be added or removed at once",
+                MAX_ALLOWLIST_UPDATES
+            ),
+        ));
+    }
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let parse_depositors = |depositors: &[String]| {
+        depositors
+            .iter()
+            .map(|depositor| Pubkey::from_str(depositor))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    "Invalid depositor pubkey".to_string(),
+                )
+            })
+    };
+    let add = parse_depositors(&payload.add)?;
+    let remove = parse_depositors(&payload.remove)?;
+    let fund_data = rpc_client
+        .get_account_data(&fund_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Fund not found".to_string(),
+            )
+        })?;
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+    ];
+    instructions.extend(
+        add.into_iter()
+            .map(|depositor| add_to_allowlist(fund_pubkey, fund_data.authority, depositor)),
+    );
+    instructions.extend(
+        remove
+            .into_iter()
+            .map(|depositor| remove_from_allowlist(fund_pubkey, fund_data.authority, depositor)),
+    );
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&fund_data.authority, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetUpdateAllowlistMsgResponse {
+        msg: base64::encode(message.serialize()),
+    }))
+}
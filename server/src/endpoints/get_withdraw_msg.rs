@@ -0,0 +1,182 @@
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::instruction::close_account;
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::{
+    sdk::{fund_valuation_accounts, withdraw},
+    state::{fund_token_account_pda, position_pda, Fund, Position},
+};
+
+use crate::{
+    utils::{get_management_fee_ixns, get_token_mint},
+    WSOL,
+};
+
+#[derive(Deserialize)]
+pub struct GetWithdrawMsgPayload {
+    pub fund: String,
+    pub depositor: String,
+    pub shares: f64,
+}
+
+#[derive(Serialize)]
+pub struct GetWithdrawMsgResponse {
+    pub msg: String,
+}
+
+pub async fn get_withdraw_msg(
+    Json(payload): Json<GetWithdrawMsgPayload>,
+    rpc_client: Arc<RpcClient>,
+) -> Result<Json<GetWithdrawMsgResponse>, (axum::http::StatusCode, String)> {
+    if payload.shares <= 0.0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Shares must be greater than 0".to_string(),
+        ));
+    }
+    let fund_pubkey = Pubkey::from_str(&payload.fund).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid fund pubkey".to_string(),
+        )
+    })?;
+    let depositor_pubkey = Pubkey::from_str(&payload.depositor).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "Invalid depositor pubkey".to_string(),
+        )
+    })?;
+    let fund_data = rpc_client.get_account_data(&fund_pubkey).await.unwrap();
+    let fund_data = Fund::try_from_bytes(&fund_data).unwrap();
+    let fund_main_token_account = fund_token_account_pda(&fund_pubkey, &fund_data.main_mint).0;
+    let main_mint_data = get_token_mint(&rpc_client, &fund_data.main_mint).await?;
+    let position_pubkey = position_pda(&fund_pubkey, &depositor_pubkey).0;
+    let depositor_main_token_account = main_mint_data.associated_token_address(&depositor_pubkey);
+    let position_data = rpc_client
+        .get_account_data(&position_pubkey)
+        .await
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Position not found".to_string(),
+            )
+        })?;
+    let position_data = Position::try_from_bytes(&position_data).unwrap();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0);
+    let unlocks_at = position_data.unlocks_at(fund_data);
+    if now < unlocks_at {
+        return Err((
+            axum::http::StatusCode::FORBIDDEN,
+            format!("Position is locked until {}", unlocks_at),
+        ));
+    }
+    match position_data.withdrawable_at(fund_data) {
+        None => {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                "The fund requires a redemption request before withdrawing".to_string(),
+            ))
+        }
+        Some(withdrawable_at) if now < withdrawable_at => {
+            return Err((
+                axum::http::StatusCode::FORBIDDEN,
+                format!("Redemption notice period ends at {}", withdrawable_at),
+            ))
+        }
+        Some(_) => {}
+    }
+
+    // Shares are minted 1:1 with the first deposit, so they use the main mint decimals.
+    let shares = (payload.shares * (10u64.pow(main_mint_data.decimals as u32) as f64)) as u64;
+
+    let mut instructions = vec![];
+
+    instructions.extend([
+        ComputeBudgetInstruction::set_compute_unit_limit(500_000),
+        ComputeBudgetInstruction::set_compute_unit_price(500_000),
+        create_associated_token_account_idempotent(
+            &depositor_pubkey,
+            &depositor_pubkey,
+            &fund_data.main_mint,
+            &main_mint_data.token_program,
+        ),
+    ]);
+
+    // The fund pays out its other mints in kind alongside the main mint.
+    let mut other_token_accounts = vec![];
+    for mint in fund_data.other_mints.iter() {
+        let mint_data = get_token_mint(&rpc_client, mint).await?;
+        instructions.push(create_associated_token_account_idempotent(
+            &depositor_pubkey,
+            &depositor_pubkey,
+            mint,
+            &mint_data.token_program,
+        ));
+        other_token_accounts.push((
+            fund_token_account_pda(&fund_pubkey, mint).0,
+            mint_data.associated_token_address(&depositor_pubkey),
+            *mint,
+            mint_data.token_program,
+        ));
+    }
+
+    // The oracles are only needed to value the fund for its management fee.
+    let valuation_accounts = if fund_data.management_fee_bps == 0 {
+        vec![]
+    } else {
+        fund_valuation_accounts(&fund_pubkey, fund_data)
+    };
+    instructions.extend(get_management_fee_ixns(
+        &depositor_pubkey,
+        fund_data,
+        &main_mint_data,
+    ));
+    instructions.push(withdraw(
+        position_pubkey,
+        depositor_pubkey,
+        fund_pubkey,
+        fund_main_token_account,
+        depositor_main_token_account,
+        fund_data.main_mint,
+        main_mint_data.token_program,
+        main_mint_data.associated_token_address(&fund_data.authority),
+        other_token_accounts,
+        valuation_accounts,
+        shares,
+    ));
+
+    if fund_data.main_mint == Pubkey::from_str(WSOL).unwrap() {
+        instructions.push(
+            close_account(
+                &spl_token::ID,
+                &depositor_main_token_account,
+                &depositor_pubkey,
+                &depositor_pubkey,
+                &[],
+            )
+            .unwrap(),
+        );
+    }
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+
+    let message =
+        Message::try_compile(&depositor_pubkey, &instructions, &[], recent_blockhash).unwrap();
+
+    Ok(Json(GetWithdrawMsgResponse {
+        msg: base64::encode(message.serialize()),
+    }))
+}
@@ -1,13 +1,35 @@
+mod get_accept_authority_msg;
 mod get_claim_payout_msg;
 mod get_create_fund_msg;
 mod get_create_payout_msg;
+mod get_deposit_in_kind_msg;
 mod get_deposit_msg;
 mod get_fund;
+mod get_funds;
+mod get_position;
+mod get_propose_authority_msg;
+mod get_reclaim_payout_msg;
+mod get_request_redemption_msg;
+mod get_set_access_policy_msg;
+mod get_set_fund_paused_msg;
 mod get_swap_msg;
+mod get_update_allowlist_msg;
+mod get_withdraw_msg;
 
+pub use get_accept_authority_msg::*;
 pub use get_claim_payout_msg::*;
 pub use get_create_fund_msg::*;
 pub use get_create_payout_msg::*;
+pub use get_deposit_in_kind_msg::*;
 pub use get_deposit_msg::*;
 pub use get_fund::*;
+pub use get_funds::*;
+pub use get_position::*;
+pub use get_propose_authority_msg::*;
+pub use get_reclaim_payout_msg::*;
+pub use get_request_redemption_msg::*;
+pub use get_set_access_policy_msg::*;
+pub use get_set_fund_paused_msg::*;
 pub use get_swap_msg::*;
+pub use get_update_allowlist_msg::*;
+pub use get_withdraw_msg::*;
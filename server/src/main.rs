@@ -62,6 +62,13 @@ async fn main() {
                 move |body| get_deposit_msg(body, rpc_client)
             }),
         )
+        .route(
+            "/get-deposit-in-kind-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_deposit_in_kind_msg(body, rpc_client)
+            }),
+        )
         .route(
             "/get-create-payout-msg",
             post({
@@ -76,6 +83,76 @@ async fn main() {
                 move |body| get_claim_payout_msg(body, rpc_client)
             }),
         )
+        .route(
+            "/get-withdraw-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_withdraw_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-funds",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_funds(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-propose-authority-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_propose_authority_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-accept-authority-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_accept_authority_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-position",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_position(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-reclaim-payout-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_reclaim_payout_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-request-redemption-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_request_redemption_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-set-access-policy-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_set_access_policy_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-set-fund-paused-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_set_fund_paused_msg(body, rpc_client)
+            }),
+        )
+        .route(
+            "/get-update-allowlist-msg",
+            post({
+                let rpc_client = Arc::clone(&rpc_client);
+                move |body| get_update_allowlist_msg(body, rpc_client)
+            }),
+        )
         .layer(cors);
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "8000".to_string());
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use steel::Pubkey;
+use yoko_program_api::{
+    merkle::{merkle_proof, merkle_root},
+    state::{allowlist_entry_pda, AccessMode, Fund},
+};
+
+use super::{get_token_balance, get_token_mint};
+
+/// Returns the access account and Merkle proof `create_position` needs for `depositor` under the
+/// fund's access policy, or an error if the depositor would be rejected.
+///
+/// `merkle_members` is the full list of depositors the fund's Merkle root was built from, which
+/// the manager shares with its depositors off chain.
+pub async fn get_position_access(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    fund_data: &Fund,
+    depositor: &Pubkey,
+    merkle_members: Option<&[Pubkey]>,
+) -> Result<(Option<Pubkey>, Vec<[u8; 32]>), (StatusCode, String)> {
+    match fund_data.access_mode() {
+        AccessMode::Open => Ok((None, vec![])),
+        AccessMode::Allowlist => {
+            let allowlist_entry = allowlist_entry_pda(fund, depositor).0;
+            if rpc_client.get_account_data(&allowlist_entry).await.is_err() {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    "Depositor is not on the fund allowlist".to_string(),
+                ));
+            }
+            Ok((Some(allowlist_entry), vec![]))
+        }
+        AccessMode::Merkle => {
+            let Some(merkle_members) = merkle_members else {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "The fund requires the list of allowed depositors".to_string(),
+                ));
+            };
+            if merkle_root(merkle_members) != fund_data.merkle_root {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Allowed depositors do not match the fund Merkle root".to_string(),
+                ));
+            }
+            let proof = merkle_proof(merkle_members, depositor).ok_or((
+                StatusCode::FORBIDDEN,
+                "Depositor is not one of the allowed depositors".to_string(),
+            ))?;
+            Ok((None, proof))
+        }
+        AccessMode::TokenGate => {
+            let token_account = get_token_mint(rpc_client, &fund_data.gate_mint)
+                .await?
+                .associated_token_address(depositor);
+            let amount = get_token_balance(rpc_client, &token_account)
+                .await
+                .unwrap_or(0);
+            if amount == 0 {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    format!("Depositor must hold {}", fund_data.gate_mint),
+                ));
+            }
+            Ok((Some(token_account), vec![]))
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::http::StatusCode;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use steel::{AccountDeserialize, Instruction, Pubkey};
+use yoko_program_api::{
+    sdk::create_position,
+    state::{position_pda, Fund, Position},
+};
+
+use super::get_position_access;
+
+/// The depositor's position, or the one `CreatePosition` would open, and whether it exists.
+pub async fn get_deposit_position(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    fund_data: &Fund,
+    depositor: &Pubkey,
+) -> (Position, bool) {
+    match rpc_client
+        .get_account_data(&position_pda(fund, depositor).0)
+        .await
+    {
+        Ok(data) => (*Position::try_from_bytes(&data).unwrap(), true),
+        Err(_) => (
+            Position {
+                authority: *depositor,
+                fund: *fund,
+                shares: 0,
+                payouts_counter: fund_data.payouts_counter,
+                deposited: 0,
+                last_deposit_ts: 0,
+                redemption_requested_at: 0,
+            },
+            false,
+        ),
+    }
+}
+
+/// Checks the deposit limits the program enforces on `amount`, the deposit's worth in main mint
+/// base units. Returns the capacity left before the deposit, or `None` when the fund has no cap.
+pub fn check_deposit_limits(
+    fund_data: &Fund,
+    position_data: &Position,
+    amount: u64,
+    main_mint_multiplier: f64,
+) -> Result<Option<u64>, (StatusCode, String)> {
+    if amount < fund_data.min_deposit {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Amount must be at least {}",
+                fund_data.min_deposit as f64 / main_mint_multiplier
+            ),
+        ));
+    }
+    let remaining_capacity = fund_data.remaining_deposit_capacity(position_data);
+    if let Some(remaining_capacity) = remaining_capacity {
+        if amount > remaining_capacity {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Amount exceeds the remaining deposit capacity of {}",
+                    remaining_capacity as f64 / main_mint_multiplier
+                ),
+            ));
+        }
+    }
+
+    Ok(remaining_capacity)
+}
+
+/// Opens the depositor's position under the fund's access policy. `merkle_members` is only
+/// needed for a fund with a Merkle access policy.
+pub async fn get_create_position_ixn(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    fund_data: &Fund,
+    depositor: &Pubkey,
+    merkle_members: Option<&[String]>,
+) -> Result<Instruction, (StatusCode, String)> {
+    let merkle_members = merkle_members
+        .map(|members| {
+            members
+                .iter()
+                .map(|member| Pubkey::from_str(member))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                "Invalid Merkle member pubkey".to_string(),
+            )
+        })?;
+    let (access_account, merkle_proof) = get_position_access(
+        rpc_client,
+        fund,
+        fund_data,
+        depositor,
+        merkle_members.as_deref(),
+    )
+    .await?;
+
+    Ok(create_position(
+        position_pda(fund, depositor).0,
+        *fund,
+        *depositor,
+        access_account,
+        merkle_proof,
+    ))
+}
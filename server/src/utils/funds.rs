@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use steel::{AccountDeserialize, Pubkey};
+use yoko_program_api::state::Fund;
+
+// Offsets into the fund account data, after the 8 byte discriminator.
+const FUND_AUTHORITY_OFFSET: usize = 8;
+const FUND_CREATOR_OFFSET: usize = 8 + 32 + 32;
+
+/// Funds currently managed by `authority`.
+pub async fn get_funds_by_authority(
+    rpc_client: &Arc<RpcClient>,
+    authority: &Pubkey,
+) -> Result<Vec<(Pubkey, Fund)>, Box<dyn std::error::Error>> {
+    get_funds_by(rpc_client, FUND_AUTHORITY_OFFSET, authority).await
+}
+
+/// Funds created by `creator`, including ones whose management has since moved to another key.
+pub async fn get_funds_by_creator(
+    rpc_client: &Arc<RpcClient>,
+    creator: &Pubkey,
+) -> Result<Vec<(Pubkey, Fund)>, Box<dyn std::error::Error>> {
+    get_funds_by(rpc_client, FUND_CREATOR_OFFSET, creator).await
+}
+
+async fn get_funds_by(
+    rpc_client: &Arc<RpcClient>,
+    offset: usize,
+    key: &Pubkey,
+) -> Result<Vec<(Pubkey, Fund)>, Box<dyn std::error::Error>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(8 + std::mem::size_of::<Fund>() as u64),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, key.as_ref())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc_client
+        .get_program_accounts_with_config(&yoko_program_api::ID, config)
+        .await?;
+
+    let mut funds = accounts
+        .into_iter()
+        .map(|(pubkey, account)| Ok((pubkey, *Fund::try_from_bytes(&account.data)?)))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    funds.sort_by_key(|(_, fund)| fund.id);
+
+    Ok(funds)
+}
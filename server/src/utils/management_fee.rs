@@ -0,0 +1,24 @@
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use steel::{Instruction, Pubkey};
+use yoko_program_api::state::Fund;
+
+use super::TokenMint;
+
+/// Creates the fund authority's main mint token account, which deposits and withdrawals pay the
+/// pending management fee into, when the fund charges one. `payer` pays for the account.
+pub fn get_management_fee_ixns(
+    payer: &Pubkey,
+    fund_data: &Fund,
+    main_mint_data: &TokenMint,
+) -> Vec<Instruction> {
+    if fund_data.management_fee_bps == 0 {
+        return vec![];
+    }
+
+    vec![create_associated_token_account_idempotent(
+        payer,
+        &fund_data.authority,
+        &main_mint_data.address,
+        &main_mint_data.token_program,
+    )]
+}
@@ -1,3 +1,21 @@
+mod access;
+mod deposit;
+mod funds;
+mod management_fee;
+mod pause;
+mod payout;
+mod protocol_config;
+mod risk;
 mod swap;
+mod token;
 
+pub use access::*;
+pub use deposit::*;
+pub use funds::*;
+pub use management_fee::*;
+pub use pause::*;
+pub use payout::*;
+pub use protocol_config::*;
+pub use risk::*;
 pub use swap::*;
+pub use token::*;
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use yoko_program_api::state::Fund;
+
+use super::get_protocol_config;
+
+/// Rejects messages the program would fail because the fund or the protocol is paused.
+pub async fn check_not_paused(
+    rpc_client: &Arc<RpcClient>,
+    fund_data: &Fund,
+) -> Result<(), (StatusCode, String)> {
+    if fund_data.is_paused() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Fund is paused".to_string(),
+        ));
+    }
+
+    check_protocol_not_paused(rpc_client).await
+}
+
+/// Rejects messages the program would fail because the protocol is paused.
+pub async fn check_protocol_not_paused(
+    rpc_client: &Arc<RpcClient>,
+) -> Result<(), (StatusCode, String)> {
+    let protocol_config = get_protocol_config(rpc_client).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Protocol config not found".to_string(),
+        )
+    })?;
+    if protocol_config.is_paused() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Protocol is paused".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,310 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
+
+use axum::http::StatusCode;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash,
+    message::{v0::Message, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
+    signature::SIGNATURE_BYTES,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use steel::{AccountDeserialize, Instruction, Pubkey};
+use yoko_program_api::{
+    sdk::{claim_payout_sol, claim_payouts, close_payout},
+    state::{payout_pda, payout_token_account_pda, position_pda, Fund, Payout, Position},
+};
+
+use crate::WSOL;
+
+use super::{get_token_balance, get_token_mint, TokenMint};
+
+/// Most payouts claimed in one message. Fewer are claimed when their instructions do not fit in
+/// one transaction with the rest of the message.
+pub const MAX_PAYOUTS_PER_CLAIM: u64 = 8;
+
+/// Compute units budgeted for each payout a message claims, estimated for the worst case: a payout
+/// in a new mint that the claim completes. It costs the depositor's associated token account (up
+/// to about 25_000 units for Token-2022), its part of a claim (seed checks of the payout and its
+/// token account and a transfer, about 30_000) and a `ClosePayout` (seed checks, a transfer of
+/// leftovers and two account closes, about 20_000). Eight payouts stay well below the 1_400_000
+/// units a transaction may use.
+pub const CLAIM_COMPUTE_UNITS_PER_PAYOUT: u32 = 75_000;
+
+/// Instructions claiming the first `payouts` payouts a position is behind on.
+pub struct PayoutClaims {
+    pub instructions: Vec<Instruction>,
+    pub payouts: u64,
+}
+
+impl PayoutClaims {
+    pub fn compute_units(&self) -> u32 {
+        self.payouts as u32 * CLAIM_COMPUTE_UNITS_PER_PAYOUT
+    }
+}
+
+/// A payout the position is behind on.
+struct PendingPayout {
+    counter: u64,
+    /// (payout, payout token account).
+    accounts: (Pubkey, Pubkey),
+    /// `None` once the payout was closed.
+    data: Option<Payout>,
+    /// Whether claiming completes the payout and it can be closed right after.
+    closes: bool,
+}
+
+/// Consecutive payouts of one mint claimed in one instruction.
+struct Claim {
+    mint: Pubkey,
+    /// (payout, payout token account) pairs.
+    payouts: Vec<(Pubkey, Pubkey)>,
+    /// (counter, rent payer) of the payouts the claim completes.
+    completed_payouts: Vec<(u64, Pubkey)>,
+}
+
+/// Claims as many of the payouts the position is behind on as fit in one transaction with
+/// `other_ixns`, up to `MAX_PAYOUTS_PER_CLAIM` and including closed ones the program skips, and
+/// closes the payouts the claims complete.
+pub async fn get_claim_payouts_ixns(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    fund_data: &Fund,
+    depositor: &Pubkey,
+    position_data: &Position,
+    other_ixns: &[Instruction],
+) -> Result<PayoutClaims, (StatusCode, String)> {
+    let last_payout_counter = fund_data
+        .payouts_counter
+        .min(position_data.payouts_counter + MAX_PAYOUTS_PER_CLAIM);
+    let counters = position_data.payouts_counter + 1..=last_payout_counter;
+    let payouts = counters
+        .clone()
+        .map(|counter| payout_pda(fund, counter).0)
+        .collect::<Vec<_>>();
+    let payouts_data = rpc_client
+        .get_multiple_accounts(&payouts)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to fetch payouts".to_string(),
+            )
+        })?;
+
+    let mut pending_payouts = vec![];
+    let mut mints = HashMap::new();
+    for ((counter, payout), payout_data) in counters.zip(payouts).zip(payouts_data) {
+        let accounts = (payout, payout_token_account_pda(&payout).0);
+        let data =
+            payout_data.map(|payout_data| *Payout::try_from_bytes(&payout_data.data).unwrap());
+
+        let closes = match &data {
+            Some(data) => {
+                data.claimed_shares + position_data.shares == data.total_shares
+                    && is_closable(rpc_client, fund_data, data, &accounts.1).await?
+            }
+            None => false,
+        };
+
+        let mint = data.map_or(fund_data.main_mint, |data| data.mint);
+        if let Entry::Vacant(entry) = mints.entry(mint) {
+            entry.insert(get_token_mint(rpc_client, &mint).await?);
+        }
+
+        pending_payouts.push(PendingPayout {
+            counter,
+            accounts,
+            data,
+            closes,
+        });
+    }
+
+    // Payouts in many mints take an associated token account and a claim each, so the message is
+    // compiled to find how many fit in a transaction.
+    let mut count = pending_payouts.len();
+    loop {
+        let instructions = claim_ixns(
+            fund,
+            fund_data,
+            depositor,
+            &pending_payouts[..count],
+            &mints,
+        );
+
+        let mut message_ixns = other_ixns.to_vec();
+        message_ixns.extend(instructions.iter().cloned());
+        if count == 0 || fits_in_transaction(depositor, &message_ixns) {
+            return Ok(PayoutClaims {
+                instructions,
+                payouts: count as u64,
+            });
+        }
+
+        count -= 1;
+    }
+}
+
+fn claim_ixns(
+    fund: &Pubkey,
+    fund_data: &Fund,
+    depositor: &Pubkey,
+    pending_payouts: &[PendingPayout],
+    mints: &HashMap<Pubkey, TokenMint>,
+) -> Vec<Instruction> {
+    let position = position_pda(fund, depositor).0;
+
+    // Each claim instruction pays out in one mint, so consecutive payouts of the same mint are
+    // claimed together and a payout in another mint starts a new claim. Payouts this claim
+    // completes are closed right after it, returning their rent to the payer.
+    let mut claims: Vec<Claim> = vec![];
+    for pending_payout in pending_payouts {
+        // Closed payouts are skipped by the program whatever the mint of the claim.
+        let Some(payout_data) = pending_payout.data else {
+            match claims.last_mut() {
+                Some(claim) => claim.payouts.push(pending_payout.accounts),
+                None => claims.push(Claim {
+                    mint: fund_data.main_mint,
+                    payouts: vec![pending_payout.accounts],
+                    completed_payouts: vec![],
+                }),
+            }
+            continue;
+        };
+
+        if !matches!(claims.last(), Some(claim) if claim.mint == payout_data.mint) {
+            claims.push(Claim {
+                mint: payout_data.mint,
+                payouts: vec![],
+                completed_payouts: vec![],
+            });
+        }
+        let claim = claims.last_mut().unwrap();
+        claim.payouts.push(pending_payout.accounts);
+        if pending_payout.closes {
+            claim
+                .completed_payouts
+                .push((pending_payout.counter, payout_data.rent_payer));
+        }
+    }
+
+    let mut instructions = vec![];
+    for Claim {
+        mint,
+        payouts,
+        completed_payouts,
+    } in claims
+    {
+        let mint_data = &mints[&mint];
+
+        // WSOL payouts are unwrapped inside the program, so the depositor needs no token account.
+        if mint == Pubkey::from_str(WSOL).unwrap() {
+            instructions.push(claim_payout_sol(position, *depositor, *fund, payouts));
+        } else {
+            instructions.extend([
+                create_associated_token_account_idempotent(
+                    depositor,
+                    depositor,
+                    &mint,
+                    &mint_data.token_program,
+                ),
+                claim_payouts(
+                    position,
+                    *depositor,
+                    mint_data.associated_token_address(depositor),
+                    *fund,
+                    mint,
+                    mint_data.token_program,
+                    payouts,
+                ),
+            ]);
+        }
+
+        instructions.extend(completed_payouts.into_iter().map(|(counter, rent_payer)| {
+            close_payout(*fund, counter, rent_payer, mint, mint_data.token_program)
+        }));
+    }
+
+    instructions
+}
+
+/// Whether a transaction of `instructions` paid and signed by `payer` fits in a packet.
+fn fits_in_transaction(payer: &Pubkey, instructions: &[Instruction]) -> bool {
+    let Ok(message) = Message::try_compile(payer, instructions, &[], Hash::default()) else {
+        return false;
+    };
+
+    // The signatures are prefixed by their count, which takes one byte below 128.
+    let signatures = message.header.num_required_signatures as usize;
+    let size = 1 + signatures * SIGNATURE_BYTES + VersionedMessage::V0(message).serialize().len();
+
+    size <= PACKET_DATA_SIZE
+}
+
+/// Whether `ClosePayout` can close the payout once the last position claims it. Tokens sent to the
+/// payout token account after its creation are swept into the fund's token account of the mint,
+/// which a swap closes once the fund sold all of that mint. Only the fund authority can create it
+/// again, so the payout is left open until then.
+async fn is_closable(
+    rpc_client: &Arc<RpcClient>,
+    fund_data: &Fund,
+    payout_data: &Payout,
+    payout_token_account: &Pubkey,
+) -> Result<bool, (StatusCode, String)> {
+    if fund_data.asset(&payout_data.mint).is_some() {
+        return Ok(true);
+    }
+
+    let unclaimed = payout_data.amount_transferred_on_creation - payout_data.claimed_amount;
+    Ok(get_token_balance(rpc_client, payout_token_account).await? <= unclaimed)
+}
+
+/// Claims the payouts the depositor's existing position is behind on, which the program requires
+/// before a deposit, in one transaction with the deposit's `other_ixns`.
+pub async fn get_deposit_claim_ixns(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    fund_data: &Fund,
+    depositor: &Pubkey,
+    position_data: &Position,
+    other_ixns: &[Instruction],
+) -> Result<PayoutClaims, (StatusCode, String)> {
+    let unclaimed_payouts = fund_data
+        .payouts_counter
+        .saturating_sub(position_data.payouts_counter);
+    if unclaimed_payouts == 0 {
+        return Ok(PayoutClaims {
+            instructions: vec![],
+            payouts: 0,
+        });
+    }
+
+    let claim_first = || {
+        (
+            StatusCode::BAD_REQUEST,
+            "Claim the unclaimed payouts of the position before depositing".to_string(),
+        )
+    };
+    if unclaimed_payouts > MAX_PAYOUTS_PER_CLAIM {
+        return Err(claim_first());
+    }
+
+    let claims = get_claim_payouts_ixns(
+        rpc_client,
+        fund,
+        fund_data,
+        depositor,
+        position_data,
+        other_ixns,
+    )
+    .await?;
+    if claims.payouts < unclaimed_payouts {
+        return Err(claim_first());
+    }
+
+    Ok(claims)
+}
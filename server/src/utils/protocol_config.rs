@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use steel::AccountDeserialize;
+use yoko_program_api::state::{protocol_config_pda, ProtocolConfig};
+
+pub async fn get_protocol_config(
+    rpc_client: &Arc<RpcClient>,
+) -> Result<ProtocolConfig, Box<dyn std::error::Error>> {
+    let protocol_config_data = rpc_client
+        .get_account_data(&protocol_config_pda().0)
+        .await?;
+    Ok(*ProtocolConfig::try_from_bytes(&protocol_config_data)?)
+}
@@ -0,0 +1,170 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::http::StatusCode;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use steel::Pubkey;
+use yoko_program_api::{
+    consts::MAX_BPS,
+    oracle::{convert_amount, OraclePrice, PriceFeed, PythPriceUpdate},
+    state::{fund_token_account_pda, Fund},
+};
+
+use super::get_token_balance;
+
+/// Checks the fund risk limits that `Swap` enforces on-chain, so that a breach is reported before
+/// the manager signs.
+///
+/// `fund_data` must already include `to_mint` when the swap adds it to the fund.
+pub async fn check_swap_risk_limits(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    fund_data: &Fund,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+    in_amount: u64,
+    out_amount: u64,
+) -> Result<(), (StatusCode, String)> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+
+    if fund_data.min_swap_interval > 0
+        && now.saturating_sub(fund_data.last_swap_at) < fund_data.min_swap_interval
+    {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            format!(
+                "The fund can swap again in {} seconds",
+                fund_data.min_swap_interval - now.saturating_sub(fund_data.last_swap_at)
+            ),
+        ));
+    }
+
+    if fund_data.max_trade_bps > 0 {
+        let source_balance = get_fund_balance(rpc_client, fund, from_mint).await?;
+        if (in_amount as u128) * (MAX_BPS as u128)
+            > (source_balance as u128) * (fund_data.max_trade_bps as u128)
+        {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "A single swap can sell at most {} bps of the fund's balance",
+                    fund_data.max_trade_bps
+                ),
+            ));
+        }
+    }
+
+    if fund_data.max_concentration_bps > 0 && *to_mint != fund_data.main_mint {
+        let main_price =
+            get_price(rpc_client, &fund_data.main_oracle, &fund_data.main_feed_id).await?;
+        let mut value = 0u128;
+        let mut to_mint_value = 0u128;
+
+        let assets = std::iter::once((
+            &fund_data.main_mint,
+            &fund_data.main_oracle,
+            &fund_data.main_feed_id,
+            fund_data.main_decimals,
+        ))
+        .chain(fund_data.other_assets());
+        for (mint, oracle, feed_id, decimals) in assets {
+            let mut balance = get_fund_balance(rpc_client, fund, mint).await?;
+            if mint == from_mint {
+                balance = balance.saturating_sub(in_amount);
+            }
+            if mint == to_mint {
+                balance = balance.saturating_add(out_amount);
+            }
+
+            let price = get_price(rpc_client, oracle, feed_id).await?;
+            let asset_value = convert_amount(
+                balance,
+                decimals,
+                &price,
+                fund_data.main_decimals,
+                &main_price,
+            )
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to value the fund".to_string(),
+                )
+            })? as u128;
+
+            value += asset_value;
+            if mint == to_mint {
+                to_mint_value = asset_value;
+            }
+        }
+
+        if to_mint_value * (MAX_BPS as u128) > value * (fund_data.max_concentration_bps as u128) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "A single mint can make up at most {} bps of the fund value",
+                    fund_data.max_concentration_bps
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Balance of the fund token account for `mint`, zero when it does not exist yet.
+async fn get_fund_balance(
+    rpc_client: &Arc<RpcClient>,
+    fund: &Pubkey,
+    mint: &Pubkey,
+) -> Result<u64, (StatusCode, String)> {
+    get_token_balance(rpc_client, &fund_token_account_pda(fund, mint).0).await
+}
+
+pub async fn get_price(
+    rpc_client: &Arc<RpcClient>,
+    oracle: &Pubkey,
+    feed_id: &[u8; 32],
+) -> Result<OraclePrice, (StatusCode, String)> {
+    let data = get_oracle_data(rpc_client, oracle).await?;
+
+    // Only an estimate, the program rejects stale prices itself.
+    PythPriceUpdate::parse_price(&data, feed_id, 0, i64::MAX).map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Invalid oracle price".to_string(),
+        )
+    })
+}
+
+/// Id of the price feed `oracle` holds, which the program stores when the fund starts holding a
+/// mint.
+pub async fn get_feed_id(
+    rpc_client: &Arc<RpcClient>,
+    oracle: &Pubkey,
+) -> Result<[u8; 32], (StatusCode, String)> {
+    let data = get_oracle_data(rpc_client, oracle).await?;
+
+    PythPriceUpdate::parse_feed_id(&data).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            "Oracle does not hold a verified price update".to_string(),
+        )
+    })
+}
+
+async fn get_oracle_data(
+    rpc_client: &Arc<RpcClient>,
+    oracle: &Pubkey,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    rpc_client.get_account_data(oracle).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Oracle account not found".to_string(),
+        )
+    })
+}
@@ -6,18 +6,22 @@ use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0::Message
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use steel::{AccountDeserialize, AccountMeta, Instruction, Pubkey};
 use yoko_program_api::{
-    sdk::{create_fund_token_account, swap},
-    state::{fund_pda, fund_token_account_pda, Fund},
+    sdk::{create_fund_token_account, swap, swap_valuation_accounts},
+    state::{fund_token_account_pda, Fund},
 };
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_swap_message(
     rpc_client: Arc<RpcClient>,
-    fund_manager_pubkey: Pubkey,
+    fund: Pubkey,
     from_mint_pubkey: Pubkey,
     to_mint_pubkey: Pubkey,
     amount: u64,
+    min_out_amount: u64,
     quote: serde_json::Value,
+    to_mint_oracle: Option<Pubkey>,
 ) -> String {
+    let fund_manager_pubkey = get_fund(&rpc_client, fund).await.unwrap().authority;
     let fund_manager = fund_manager_pubkey.to_string();
     // let from_mint = from_mint_pubkey.to_string();
     // let to_mint = to_mint_pubkey.to_string();
@@ -28,6 +32,9 @@ pub async fn get_swap_message(
         .await
         .unwrap();
 
+    let swap_program =
+        Pubkey::from_str(swap_ixn["swapInstruction"]["programId"].as_str().unwrap()).unwrap();
+
     let jupiter_data_base64 = swap_ixn["swapInstruction"]["data"].as_str().unwrap();
     let jupiter_data = base64::decode(jupiter_data_base64).unwrap();
 
@@ -66,7 +73,7 @@ pub async fn get_swap_message(
 
                         solana_sdk::address_lookup_table_account::AddressLookupTableAccount {
                             key: *address,
-                            addresses: lookup_table.addresses.into_iter().copied().collect(),
+                            addresses: lookup_table.addresses.iter().copied().collect(),
                         }
                     })
                     .unwrap()
@@ -76,6 +83,13 @@ pub async fn get_swap_message(
     .await
     .into_iter()
     .collect::<Vec<_>>();
+    let from_token_program = rpc_client
+        .get_account(&from_mint_pubkey)
+        .await
+        .unwrap()
+        .owner;
+    let to_token_program = rpc_client.get_account(&to_mint_pubkey).await.unwrap().owner;
+
     let mut instructions = vec![];
 
     instructions.extend([
@@ -87,17 +101,24 @@ pub async fn get_swap_message(
         fund_manager_pubkey,
         from_mint_pubkey,
         to_mint_pubkey,
+        from_token_program,
+        to_token_program,
     ));
 
     instructions.extend(
         get_swap_ixns(
             &rpc_client,
-            fund_manager_pubkey,
+            fund,
+            swap_program,
             &jupiter_data,
             jupiter_accounts,
             from_mint_pubkey,
             to_mint_pubkey,
+            from_token_program,
+            to_token_program,
+            to_mint_oracle,
             amount,
+            min_out_amount,
         )
         .await
         .unwrap(),
@@ -164,7 +185,7 @@ async fn get_jup_swap_ixn(
     };
 
     let response = client
-        .post(&format!("{}/swap-instructions", JUP_API_ENDPOINT))
+        .post(format!("{}/swap-instructions", JUP_API_ENDPOINT))
         .header("Accept", "application/json")
         .header("Content-Type", "application/json")
         .json(&data)
@@ -176,65 +197,90 @@ async fn get_jup_swap_ixn(
     Ok(response)
 }
 
-async fn get_fund_source_token_account(
-    rpc_client: &Arc<RpcClient>,
-    fund_manager_pubkey: Pubkey,
-    from_mint: Pubkey,
-) -> Result<Pubkey, Box<dyn std::error::Error>> {
-    let fund = get_fund(rpc_client, fund_manager_pubkey).await?.0;
-    let token_account = fund_token_account_pda(&fund, &from_mint).0;
-    Ok(token_account)
+fn get_fund_source_token_account(fund: Pubkey, from_mint: Pubkey) -> Pubkey {
+    fund_token_account_pda(&fund, &from_mint).0
 }
 
 // returns (fund_destination_token_account, need_to_create_fund_token_account)
-async fn get_fund_destination_token_account(
-    rpc_client: &Arc<RpcClient>,
-    fund_manager_pubkey: Pubkey,
+fn get_fund_destination_token_account(
+    fund: Pubkey,
+    fund_data: &Fund,
     to_mint: Pubkey,
-) -> Result<(Pubkey, bool), Box<dyn std::error::Error>> {
-    let (fund, fund_data) = get_fund(rpc_client, fund_manager_pubkey).await?;
-    Ok((
+) -> (Pubkey, bool) {
+    (
         fund_token_account_pda(&fund, &to_mint).0,
         if to_mint == fund_data.main_mint {
             false
         } else {
             !fund_data.other_mints.contains(&to_mint)
         },
-    ))
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn get_swap_ixns(
     rpc_client: &Arc<RpcClient>,
-    fund_manager_pubkey: Pubkey,
+    fund: Pubkey,
+    swap_program: Pubkey,
     jupiter_route_cpi_data: &[u8],
     jupiter_accounts_metas: Vec<AccountMeta>,
     from_mint: Pubkey,
     to_mint: Pubkey,
+    from_token_program: Pubkey,
+    to_token_program: Pubkey,
+    to_mint_oracle: Option<Pubkey>,
     in_amount: u64,
+    min_out_amount: u64,
 ) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
-    let fund = fund_pda(&fund_manager_pubkey).0;
+    let mut fund_data = get_fund(rpc_client, fund).await?;
+    let fund_manager_pubkey = fund_data.authority;
 
     let (fund_destination_token_account, need_to_create_fund_token_account) =
-        get_fund_destination_token_account(rpc_client, fund_manager_pubkey, to_mint).await?;
+        get_fund_destination_token_account(fund, &fund_data, to_mint);
+
+    let source_oracle = fund_data
+        .asset(&from_mint)
+        .ok_or("fund does not hold from_mint")?
+        .0;
 
     let mut instructions = vec![];
-    if need_to_create_fund_token_account {
+    let destination_oracle = if need_to_create_fund_token_account {
+        let to_mint_oracle =
+            to_mint_oracle.ok_or("to_mint_oracle is required to create a fund token account")?;
         instructions.push(create_fund_token_account(
             fund,
             fund_manager_pubkey,
             fund_destination_token_account,
             to_mint,
+            to_mint_oracle,
+            to_token_program,
         ));
-    }
+        // Feed ids and decimals do not matter for the valuation accounts.
+        fund_data.insert_other_mint(to_mint, to_mint_oracle, [0; 32], 0);
+        to_mint_oracle
+    } else {
+        fund_data
+            .asset(&to_mint)
+            .ok_or("fund does not hold to_mint")?
+            .0
+    };
 
     instructions.push(swap(
         fund_manager_pubkey,
         fund,
-        get_fund_source_token_account(rpc_client, fund_manager_pubkey, from_mint).await?,
+        get_fund_source_token_account(fund, from_mint),
         fund_destination_token_account,
-        Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap(),
+        source_oracle,
+        destination_oracle,
+        swap_program,
+        from_mint,
+        to_mint,
+        from_token_program,
+        to_token_program,
+        swap_valuation_accounts(&fund, &fund_data),
         jupiter_accounts_metas,
         in_amount,
+        min_out_amount,
         jupiter_route_cpi_data,
     ));
 
@@ -245,28 +291,29 @@ fn get_create_fund_manager_atas_ixns(
     fund_manager: Pubkey,
     from_mint: Pubkey,
     to_mint: Pubkey,
+    from_token_program: Pubkey,
+    to_token_program: Pubkey,
 ) -> Vec<Instruction> {
     vec![
         create_associated_token_account_idempotent(
             &fund_manager,
             &fund_manager,
             &from_mint,
-            &spl_token::ID,
+            &from_token_program,
         ),
         create_associated_token_account_idempotent(
             &fund_manager,
             &fund_manager,
             &to_mint,
-            &spl_token::ID,
+            &to_token_program,
         ),
     ]
 }
 
 async fn get_fund(
     rpc_client: &Arc<RpcClient>,
-    fund_manager_pubkey: Pubkey,
-) -> Result<(Pubkey, Fund), Box<dyn std::error::Error>> {
-    let fund = fund_pda(&fund_manager_pubkey).0;
+    fund: Pubkey,
+) -> Result<Fund, Box<dyn std::error::Error>> {
     let fund_data = rpc_client.get_account_data(&fund).await?;
-    Ok((fund, *Fund::try_from_bytes(&fund_data)?))
+    Ok(*Fund::try_from_bytes(&fund_data)?)
 }
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use steel::Pubkey;
+use yoko_program_api::token::{is_token_program, transfer_fee, unpack_mint, unpack_token_account};
+
+/// A mint together with the token program that owns it, either classic SPL Token or Token-2022.
+pub struct TokenMint {
+    pub address: Pubkey,
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    data: Vec<u8>,
+}
+
+impl TokenMint {
+    pub fn associated_token_address(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.address, &self.token_program)
+    }
+
+    /// Fee the mint withholds when `amount` is transferred in `epoch`.
+    pub fn transfer_fee(&self, epoch: u64, amount: u64) -> u64 {
+        transfer_fee(&self.data, epoch, amount).unwrap_or(0)
+    }
+}
+
+pub async fn get_token_mint(
+    rpc_client: &Arc<RpcClient>,
+    mint: &Pubkey,
+) -> Result<TokenMint, (StatusCode, String)> {
+    let account = rpc_client
+        .get_account(mint)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Mint {} not found", mint)))?;
+
+    let invalid_mint = || (StatusCode::BAD_REQUEST, format!("{} is not a mint", mint));
+    if !is_token_program(&account.owner) {
+        return Err(invalid_mint());
+    }
+    let decimals = unpack_mint(&account.data)
+        .map_err(|_| invalid_mint())?
+        .decimals;
+
+    Ok(TokenMint {
+        address: *mint,
+        token_program: account.owner,
+        decimals,
+        data: account.data,
+    })
+}
+
+/// Balance of a token account of either token program, zero when it does not exist.
+pub async fn get_token_balance(
+    rpc_client: &Arc<RpcClient>,
+    token_account: &Pubkey,
+) -> Result<u64, (StatusCode, String)> {
+    match rpc_client.get_account_data(token_account).await {
+        Ok(data) => unpack_token_account(&data)
+            .map(|account| account.amount)
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("{} is not a token account", token_account),
+                )
+            }),
+        Err(_) => Ok(0),
+    }
+}
+
+pub async fn get_epoch(rpc_client: &Arc<RpcClient>) -> Result<u64, (StatusCode, String)> {
+    rpc_client
+        .get_epoch_info()
+        .await
+        .map(|epoch_info| epoch_info.epoch)
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to fetch the current epoch".to_string(),
+            )
+        })
+}